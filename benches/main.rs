@@ -3,6 +3,7 @@ use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criteri
 use lang_macros::languages;
 use lang_inner::compress::UStream;
 use lang_inner::{LanguageRaw, Table};
+use lang::LookupError;
 
 languages!(en);
 
@@ -8,6 +8,7 @@
 
 #[cfg(feature = "macro")]
 mod macro_impls;
+pub mod pack;
 mod prelude;
 pub mod ser;
 mod unique_stream;
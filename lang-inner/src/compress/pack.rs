@@ -0,0 +1,147 @@
+//! # Binary language packs
+//!
+//! A language pack holds a language and its three compressed transition tables in a single binary
+//! blob so custom languages can be shipped precompiled. Loading a pack skips generating and
+//! compressing the tables.
+//!
+//! All integers are big-endian. The layout is:
+//!
+//! | Bytes | Contents |
+//! |---|---|
+//! | 4 | [`MAGIC`] |
+//! | 2 | Format [`VERSION`] |
+//! | 4 + n | Length of the canonical [fckl text](crate::LanguageRaw::to_fckl) then the UTF-8 text |
+//! | .. | Transition, tt, and td [`UStream`]s. See [`serialize_bin`] |
+//! | 4 | CRC-32 checksum of all the preceding bytes |
+
+use std::fmt::{Display, Formatter};
+use crate::compress::ser::{deserialize_bin, serialize_bin, DeserializeBin, SerializeBin};
+use crate::compress::UStream;
+use crate::{FcklError, LanguageRaw};
+
+/// Magic bytes at the start of every language pack
+pub const MAGIC: [u8; 4] = *b"fckp";
+/// Current language pack format version
+pub const VERSION: u16 = 1;
+
+/// Owned compressed transition, tt, and td tables
+pub type Tables = (
+    UStream<u16, Vec<u16>, Vec<u16>, Vec<usize>>,
+    UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>>,
+    UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>>,
+);
+
+/// # Language pack
+///
+/// Holds the fckl text of a language and its compressed tables. The language is parsed from the
+/// text with [`language`](Self::language)
+pub struct LanguagePack {
+    /// Canonical fckl text of the language
+    pub text: String,
+    /// Compressed transition, tt, and td tables
+    pub tables: Tables,
+}
+
+/// # Language pack error
+#[derive(Clone, PartialEq, Debug)]
+pub enum PackError {
+    /// Bytes don't start with [`MAGIC`]
+    Magic,
+    /// Pack was made with an unsupported format version
+    Version(u16),
+    /// Checksum doesn't match the pack contents
+    Checksum {
+        /// Checksum stored in the pack
+        expected: u32,
+        /// Checksum of the pack contents
+        found: u32,
+    },
+    /// Pack ended early or has bytes after the checksum
+    Length,
+    /// Language text is not valid UTF-8
+    Text,
+    /// Tables have row offsets outside of the table stream, transitions to rows that don't exist,
+    /// or different numbers of rows
+    Tables,
+}
+
+impl Display for PackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackError::Magic => write!(f, "not a language pack"),
+            PackError::Version(v) => write!(f, "unsupported language pack version {} (expected {})", v, VERSION),
+            PackError::Checksum { expected, found } => write!(f, "checksum mismatch: expected {:08x}, found {:08x}", expected, found),
+            PackError::Length => write!(f, "incorrect language pack length"),
+            PackError::Text => write!(f, "language text is not valid UTF-8"),
+            PackError::Tables => write!(f, "invalid language tables"),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+impl LanguagePack {
+    /// Make a new language pack from a language and its compressed tables
+    pub fn new(l: &LanguageRaw<'_>, tables: Tables) -> Self {
+        Self { text: l.to_fckl(), tables }
+    }
+
+    /// Parse the language held by the pack
+    pub fn language(&self) -> Result<LanguageRaw<'_>, FcklError> {
+        LanguageRaw::from_text(&self.text)
+    }
+
+    /// Serialize the pack into bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        VERSION.serialize(&mut out);
+        self.text.as_bytes().to_vec().serialize(&mut out);
+        out.extend(serialize_bin((&self.tables.0, &self.tables.1, &self.tables.2)));
+        crc32(&out).serialize(&mut out);
+        out
+    }
+
+    /// Deserialize a pack from bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PackError> {
+        if bytes.get(..4) != Some(&MAGIC) { return Err(PackError::Magic) }
+        if bytes.len() < 10 { return Err(PackError::Length) }
+        let (body, checksum) = bytes.split_at(bytes.len() - 4);
+        let mut iter = body[4..].iter().copied();
+        let version = <u16 as DeserializeBin<_>>::deserialize(&mut iter).ok_or(PackError::Length)?;
+        if version != VERSION { return Err(PackError::Version(version)) }
+        let expected = <u32 as DeserializeBin<_>>::deserialize(&mut checksum.iter().copied()).ok_or(PackError::Length)?;
+        let found = crc32(body);
+        if expected != found { return Err(PackError::Checksum { expected, found }) }
+        let text = <Vec<u8> as DeserializeBin<_>>::deserialize(&mut iter).ok_or(PackError::Length)?;
+        let text = String::from_utf8(text).map_err(|_| PackError::Text)?;
+        let tables = deserialize_bin(&mut iter).ok_or(PackError::Length)?;
+        if iter.next().is_some() { return Err(PackError::Length) }
+        if !(valid(&tables.0) && valid(&tables.1) && valid(&tables.2)) { return Err(PackError::Tables) }
+        let rows = tables.0.offsets.len();
+        if tables.1.offsets.len() != rows || tables.2.offsets.len() != rows { return Err(PackError::Tables) }
+        if tables.0.stream.iter().any(|t| *t as usize >= rows) { return Err(PackError::Tables) }
+        Ok(Self { text, tables })
+    }
+}
+
+/// Check every row of a table lies inside the table stream
+fn valid<D: Copy + crate::compress::Zero + PartialEq>(t: &UStream<D, Vec<D>, Vec<u16>, Vec<usize>>) -> bool {
+    t.origin.len() == t.stream.len() && t.offsets.iter().all(|o| o + 256 <= t.stream.len())
+}
+
+/// CRC-32 (ISO-HDLC) checksum
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for b in bytes {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 }
+        }
+    }
+    !crc
+}
+
+#[test]
+fn check_crc32() {
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926)
+}
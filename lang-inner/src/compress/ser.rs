@@ -1,4 +1,4 @@
-use crate::compress::{Zero, UStream};
+use crate::compress::{Zero, UStream, pack::Tables};
 use crate::Table;
 
 /// # Serialization for transition maps
@@ -129,17 +129,8 @@ impl<T: Iterator<Item = u8>, I: DeserializeBin<T>> DeserializeBin<T> for Vec<I>
     }
 }
 
-pub fn serialize_bin<T: Iterator<Item = u8>>(
-    iter: &mut T,
-) -> Option<(UStream<u16, Vec<u16>, Vec<u16>, Vec<usize>>, UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>>, UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>>)> {
-    Some((
-        <UStream<u16, Vec<u16>, Vec<u16>, Vec<usize>> as DeserializeBin<T>>::deserialize(iter)?,
-        <UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>> as DeserializeBin<T>>::deserialize(iter)?,
-        <UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>> as DeserializeBin<T>>::deserialize(iter)?
-    ))
-}
-
-pub fn deserialize_bin<T, M, N>((transition, tt, td): (T, M, N)) -> Vec<u8>
+/// Serialize the transition, tt, and td tables into bytes. Inverse of [`deserialize_bin`]
+pub fn serialize_bin<T, M, N>((transition, tt, td): (T, M, N)) -> Vec<u8>
 where
     T: Table<u16> + SerializeBin,
     M: Table<u8> + SerializeBin,
@@ -152,6 +143,17 @@ where
     out
 }
 
+/// Deserialize the transition, tt, and td tables from a byte stream. Inverse of [`serialize_bin`]
+pub fn deserialize_bin<T: Iterator<Item = u8>>(
+    iter: &mut T,
+) -> Option<Tables> {
+    Some((
+        <UStream<u16, Vec<u16>, Vec<u16>, Vec<usize>> as DeserializeBin<T>>::deserialize(iter)?,
+        <UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>> as DeserializeBin<T>>::deserialize(iter)?,
+        <UStream<u8, Vec<u8>, Vec<u16>, Vec<usize>> as DeserializeBin<T>>::deserialize(iter)?
+    ))
+}
+
 #[test]
 fn test() {
     let n = vec![[0u8; 256], [4u8; 256], [1u8; 256]];
@@ -64,11 +64,12 @@ impl<D: Copy + Clone + Sized + Zero + PartialEq + std::fmt::Debug + std::fmt::Di
         let mut offsets = Vec::<usize>::new();
         for (row_index, row) in l.iter().enumerate() {
             let mut offset = 0;
+            // positions of the non-zero elements. Only these need checking for each offset
+            let non_zero = (0..256).filter(|p| row[*p] != *D::ZERO).collect::<Vec<_>>();
             'main: while offset < stream.len() {
-                // the range ensures we only check elements that can fit inside the stream.
+                // the take while ensures we only check elements that can fit inside the stream.
                 // this means we don't need to check this each iteration
-                // the filter makes sure we're only looking at non-zero elements
-                for (p, _) in row[..256.min(stream.len() - offset)].iter().enumerate().filter(|(_, &e)| e != *D::ZERO) {
+                for &p in non_zero.iter().take_while(|p| **p < stream.len() - offset) {
                     // if elem is zero, it can fit. if it's non-zero, the stream value must be zero
                     // for it to fit. If it's non-zero in the stream, we start again after
                     // incrementing the offset
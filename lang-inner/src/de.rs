@@ -1,42 +1,139 @@
 use crate::*;
-use std::str::FromStr;
+use std::fmt::{Display, Formatter};
+use std::str::SplitWhitespace;
 
-macro_rules! fields {
-	($s:ident, $($n:ident),*$(,)?) => {$(fields!(@inner, $s, $n);)*};
-	(@inner, $s:ident, $n:ident) => {let $n = if let Some(l) = $s.next() {
-		if let Ok(res) = l.split_whitespace().collect::<Vec<_>>().try_into() { res } else {
-			return Err(concat!("Incorrect number of elements for ", stringify!($n), " line").to_string())
-		}
-	} else {
-		return Err(concat!("Expected ", stringify!($n), " line: [&str: _]").to_string())
-	};};
+/// # fckl parsing error
+///
+/// Returned when an fckl file can't be deserialized. Holds where the error is and what was
+/// expected there
+#[derive(Clone, PartialEq, Debug)]
+pub struct FcklError {
+    /// fckl line the error is on. Starts from 1
+    pub line: usize,
+    /// Section of the fckl file being parsed such as `"control keywords"`
+    pub section: &'static str,
+    /// Field expected
+    pub expected: String,
+    /// Text found instead. `None` if nothing was found
+    pub found: Option<String>,
+}
+
+impl Display for FcklError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {} ({}): expected {}, ", self.line, self.section, self.expected)?;
+        match &self.found {
+            Some(found) => write!(f, "found {:?}", found),
+            None => write!(f, "found nothing"),
+        }
+    }
+}
+
+impl std::error::Error for FcklError {}
+
+/// Line iterator over an fckl file that keeps track of the current line number
+pub(crate) struct Lines<'a> {
+    lines: std::str::Lines<'a>,
+    /// Current line number. Starts from 1 once the first line has been read
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Self { lines: s.lines(), line: 0 }
+    }
+
+    /// Get the next line or an error if there are no more lines
+    pub fn next(&mut self, section: &'static str, expected: &str) -> Result<&'a str, FcklError> {
+        self.line += 1;
+        match self.lines.next() {
+            Some(l) => Ok(l),
+            None => Err(self.error(section, expected, None)),
+        }
+    }
+
+    /// Get the next line as whitespace separated words
+    pub fn words(&mut self, section: &'static str) -> Result<Words<'a>, FcklError> {
+        let line = self.line + 1;
+        let text = self.next(section, "line")?;
+        Ok(Words { words: text.split_whitespace(), text, end: 0, line, section })
+    }
+
+    /// Make an error on the current line
+    pub fn error(&self, section: &'static str, expected: &str, found: Option<&str>) -> FcklError {
+        FcklError {
+            line: self.line,
+            section,
+            expected: expected.to_string(),
+            found: found.map(str::to_string),
+        }
+    }
+}
+
+/// Whitespace separated words on a single fckl line
+pub(crate) struct Words<'a> {
+    words: SplitWhitespace<'a>,
+    /// Full line text
+    text: &'a str,
+    /// Byte offset of the end of the last word read
+    end: usize,
+    line: usize,
+    section: &'static str,
+}
+
+impl<'a> Words<'a> {
+    /// Get the next word or an error if there are no more words on the line
+    pub fn next(&mut self, expected: &str) -> Result<&'a str, FcklError> {
+        match self.words.next() {
+            Some(w) => {
+                self.end = w.as_ptr() as usize - self.text.as_ptr() as usize + w.len();
+                Ok(w)
+            },
+            None => Err(self.error(expected, None)),
+        }
+    }
+
+    /// Get the rest of the line after the last word read, with whitespace trimmed
+    pub fn rest(self) -> &'a str {
+        self.text[self.end..].trim()
+    }
+
+    /// Check there are no words left on the line
+    pub fn end(mut self) -> Result<(), FcklError> {
+        match self.words.next() {
+            Some(w) => Err(self.error("end of line", Some(w))),
+            None => Ok(()),
+        }
+    }
+
+    /// Make an error on the line
+    pub fn error(&self, expected: &str, found: Option<&str>) -> FcklError {
+        FcklError {
+            line: self.line,
+            section: self.section,
+            expected: expected.to_string(),
+            found: found.map(str::to_string),
+        }
+    }
 }
 
 pub(crate) trait Deserialize<'a> {
-    fn deserialize<T: Iterator<Item = &'a str>>(s: &mut T) -> Result<Self, String>
+    fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError>
     where
         Self: Sized;
 }
 
 impl<'a> Deserialize<'a> for LanguageRaw<'a> {
-    fn deserialize<T: Iterator<Item = &'a str>>(s: &mut T) -> Result<Self, String> {
-        let (left_right, name) = if let Some(l) = s.next() {
-            let tmp: [&str; 3] = l
-                .split_whitespace()
-                .collect::<Vec<_>>()
-                .try_into()
-                .expect("Unable to parse name: incorrect number of values");
-            let lr = match tmp[0] {
-                "{" => true,
-                "}" => false,
-                _ => return Err(format!("{} != '{{' | '}}'", tmp[0]))
-            };
-            (lr, (tmp[1], tmp[2]))
-        } else {
-            return Err("Expected name line: (&str, &str)".to_string());
+    fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> {
+        let mut line = s.words("name")?;
+        let left_right = match line.next("'{' or '}'")? {
+            "{" => true,
+            "}" => false,
+            t => return Err(line.error("'{' or '}'", Some(t)))
         };
+        let name = (line.next("language name")?, line.next("language code")?);
+        let aliases = line.rest();
         Ok(Self {
-            name, left_right,
+            name, aliases, left_right,
             keywords: Keywords::deserialize(s)?,
             messages: Messages::deserialize(s)?,
         })
@@ -44,7 +141,7 @@ impl<'a> Deserialize<'a> for LanguageRaw<'a> {
 }
 
 impl<'a> Deserialize<'a> for Messages<'a> {
-    fn deserialize<T: Iterator<Item = &'a str>>(s: &mut T) -> Result<Self, String> {
+    fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> {
         Ok(Self {
             errors: Errors::deserialize(s)?,
             warnings: Warns::deserialize(s)?,
@@ -17,7 +17,9 @@
 pub mod compress;
 mod de;
 pub mod prelude;
+mod se;
 pub mod tables;
 pub mod verify;
 
 pub use prelude::*;
+pub use de::FcklError;
@@ -2,7 +2,8 @@
 
 use std::ops::Index;
 use std::str::FromStr;
-use crate::de::Deserialize;
+use crate::de::{Deserialize, FcklError, Lines};
+use crate::se::{line, Serialize};
 
 /// # Error message and descriptors
 ///
@@ -11,7 +12,7 @@ use crate::de::Deserialize;
 #[warn(missing_docs)]
 pub struct Errors<'a> {
     /// Language based errors
-    pub e00: [&'a str; 7],
+    pub e00: [&'a str; 11],
     pub e01: [&'a str; 2],
     pub e02: [&'a str; 9],
     pub e03: [&'a str; 1],
@@ -19,17 +20,16 @@ pub struct Errors<'a> {
 }
 
 impl<'a> Deserialize<'a> for Errors<'a> {
-    fn deserialize<T: Iterator<Item = &'a str>>(s: &mut T) -> Result<Self, String> {
+    fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> {
 		macro_rules! field {
 			($name:ident, $l:literal) => {
-				let $name = (0..$l)
-					.map(|i| s.next().expect(&*format!("Expected {}{:>20}", stringify!($name), i)))
-					.collect::<Vec<_>>()
-					.try_into()
-					.unwrap();
+				let mut $name = [""; $l];
+				for (i, t) in $name.iter_mut().enumerate() {
+					*t = s.next("errors", &format!("{}{:02}", stringify!($name), i + 1))?;
+				}
 			};
 		}
-		field!(e00, 7);
+		field!(e00, 11);
 		field!(e01, 2);
 		field!(e02, 9);
 		field!(e03, 1);
@@ -38,6 +38,14 @@ impl<'a> Deserialize<'a> for Errors<'a> {
     }
 }
 
+impl Serialize for Errors<'_> {
+    fn serialize(&self, out: &mut String) {
+        for l in self.e00.iter().chain(&self.e01).chain(&self.e02).chain(&self.e03).chain(&self.e04) {
+            line(out, l)
+        }
+    }
+}
+
 impl<'a> Index<(u8, u8)> for Errors<'a> {
     type Output = str;
     
@@ -59,7 +67,8 @@ impl<'a> Index<(u8, u8)> for Errors<'a> {
 #[derive(Copy, Clone)]
 #[warn(missing_docs)]
 pub struct Warns<'a> {
-    pub w00: [&'a str; 0],
+    /// Language based warnings
+    pub w00: [&'a str; 1],
     pub w01: [&'a str; 0],
     pub w02: [&'a str; 0],
     pub w03: [&'a str; 0],
@@ -67,17 +76,16 @@ pub struct Warns<'a> {
 }
 
 impl<'a> Deserialize<'a> for Warns<'a> {
-    fn deserialize<T: Iterator<Item = &'a str>>(s: &mut T) -> Result<Self, String> {
+    fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> {
 		macro_rules! field {
 			($name:ident, $l:literal) => {
-				let $name = (0..$l)
-					.map(|i| s.next().expect(&*format!("Expected {}{:>20}", stringify!($name), i)))
-					.collect::<Vec<_>>()
-					.try_into()
-					.unwrap();
+				let mut $name = [""; $l];
+				for (i, t) in $name.iter_mut().enumerate() {
+					*t = s.next("warnings", &format!("{}{:02}", stringify!($name), i + 1))?;
+				}
 			};
 		}
-		field!(w00, 0);
+		field!(w00, 1);
 		field!(w01, 0);
 		field!(w02, 0);
 		field!(w03, 0);
@@ -86,6 +94,14 @@ impl<'a> Deserialize<'a> for Warns<'a> {
     }
 }
 
+impl Serialize for Warns<'_> {
+    fn serialize(&self, out: &mut String) {
+        for l in self.w00.iter().chain(&self.w01).chain(&self.w02).chain(&self.w03).chain(&self.w04) {
+            line(out, l)
+        }
+    }
+}
+
 impl<'a> Index<(u8, u8)> for Warns<'a> {
     type Output = str;
     
@@ -113,15 +129,23 @@ pub struct CLIKeywords<'a> {
 }
 
 impl<'a> Deserialize<'a> for CLIKeywords<'a> {
-    fn deserialize<T: Iterator<Item = &'a str>>(s: &mut T) -> Result<Self, String> {
+    fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> {
         Ok(Self {
-            desc: s.next().expect("Expected CLI desc: &str"),
+            desc: s.next("CLI description", "description")?,
             commands: CLICommands::deserialize(s)?,
             args: CLIArgs::deserialize(s)?,
         })
     }
 }
 
+impl Serialize for CLIKeywords<'_> {
+    fn serialize(&self, out: &mut String) {
+        line(out, self.desc);
+        self.commands.serialize(out);
+        self.args.serialize(out);
+    }
+}
+
 /// CLI commands. All are of the type (command word, help)
 #[derive(Copy, Clone)]
 pub struct CLICommands<'a> {
@@ -153,21 +177,14 @@ impl CLICommands<'_> {
 }
 
 impl<'a> Deserialize<'a> for CLICommands<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-		let cmd_has_spaces = "Command has spaces".to_string();
-		let missing_line = "Missing line".to_string();
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
 		macro_rules! field {
 			($name:ident) => {
 				let $name = {
-					let cmd = match s.next() {
-						Some(cmd) => if cmd.contains(" ") { return Err(cmd_has_spaces) } else { cmd },
-						None => return Err(missing_line)
-					};
-					let desc = match s.next() {
-						Some(desc) => desc,
-						None => return Err(missing_line)
-					};
-					(cmd, desc)
+					let name = &stringify!($name)[2..];
+					let cmd = s.next("CLI commands", name)?;
+					if cmd.contains(' ') { return Err(s.error("CLI commands", name, Some(cmd))) }
+					(cmd, s.next("CLI commands", &format!("{} help", name))?)
 				};
 			};
 		}
@@ -186,6 +203,15 @@ impl<'a> Deserialize<'a> for CLICommands<'a> {
 	}
 }
 
+impl Serialize for CLICommands<'_> {
+	fn serialize(&self, out: &mut String) {
+		for (cmd, help) in *self {
+			line(out, cmd);
+			line(out, help);
+		}
+	}
+}
+
 impl<'a> IntoIterator for CLICommands<'a> {
 	type Item = (&'a str, &'a str);
 	type IntoIter = <Vec<(&'a str, &'a str)> as IntoIterator>::IntoIter;
@@ -229,31 +255,34 @@ impl CLIArgs<'_> {
 }
 
 impl<'a> Deserialize<'a> for CLIArgs<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
 		macro_rules! fields {
-		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: Deserialize::deserialize(s)?,)* })};
+		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: arg(s, &stringify!($name)[2..])?,)* })};
 		}
 		fields!(k_help, k_path, k_git, k_dump_llvm, k_no_build, k_test, k_raw, k_target, k_output, k_comment)
 	}
 }
 
-impl<'a> Deserialize<'a> for (&'a str, char, &'a str) {
-    fn deserialize<T: Iterator<Item = &'a str>>(s: &mut T) -> Result<Self, String> {
-        if let Some(l) = s.next() {
-            let tmp: [&str; 2] = l
-                .split_whitespace()
-                .collect::<Vec<_>>()
-                .try_into()
-                .expect("Arg first line pare error: Incorrect number of arguments given");
-            Ok((
-                tmp[0],
-                char::from_str(tmp[1]).expect("Second arg value must be a char"),
-                s.next().expect("Expected Arg second line: &str"),
-            ))
-        } else {
-            return Err("Expected Arg first line: &str, char".to_string());
-        }
-    }
+impl Serialize for CLIArgs<'_> {
+	fn serialize(&self, out: &mut String) {
+		for (long, short, help) in *self {
+			out.push_str(long);
+			out.push(' ');
+			out.push(short);
+			out.push('\n');
+			line(out, help);
+		}
+	}
+}
+
+/// Deserialize a single CLI argument. The first line is `long short` and the second is the help
+fn arg<'a>(s: &mut Lines<'a>, name: &str) -> Result<(&'a str, char, &'a str), FcklError> {
+    let mut line = s.words("CLI arguments")?;
+    let long = line.next(&format!("{} long flag", name))?;
+    let short = line.next(&format!("{} short flag", name))?;
+    let short = char::from_str(short).map_err(|_| line.error(&format!("{} short flag character", name), Some(short)))?;
+    line.end()?;
+    Ok((long, short, s.next("CLI arguments", &format!("{} help", name))?))
 }
 
 impl<'a> IntoIterator for CLIArgs<'a> {
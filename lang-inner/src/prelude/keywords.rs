@@ -1,7 +1,8 @@
 //! Keywords struct and all it's constituent structs
 
 use std::str::FromStr;
-use crate::de::Deserialize;
+use crate::de::{Deserialize, FcklError, Lines};
+use crate::se::{words, Serialize};
 
 /// # Keywords struct
 ///
@@ -25,7 +26,7 @@ pub struct Keywords<'a> {
 }
 
 impl<'a> Deserialize<'a> for Keywords<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
 		macro_rules! fields {
 		    ($($name:ident),*$(,)?) => { Ok(Self { $($name: Deserialize::deserialize(s)?),* }) };
 		}
@@ -33,6 +34,18 @@ impl<'a> Deserialize<'a> for Keywords<'a> {
 	}
 }
 
+impl Serialize for Keywords<'_> {
+	fn serialize(&self, out: &mut String) {
+		self.digits.serialize(out);
+		self.keywords.serialize(out);
+		self.type_kwds.serialize(out);
+		self.builtins.serialize(out);
+		self.bool.serialize(out);
+		self.manifest_keys.serialize(out);
+		self.compile_words.serialize(out);
+	}
+}
+
 /// # Digits type
 ///
 /// See [DigitsRaw]
@@ -45,52 +58,45 @@ pub enum Digits {
 }
 
 impl<'a> Deserialize<'a> for Digits {
-    fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-        if let Some(ln) = s.next() {
-            let mut digits = Vec::with_capacity(26);
-            for d in ln.split_whitespace() {
-                if let Ok(d) = char::from_str(d) {
-                    digits.push(d)
-                } else {
-                    return Err(format!("{:?} is not a character", d))
-                }
-            }
-            let digits_u8array = digits.iter().map(|t| {
-                let temp = (*t as u32).to_be_bytes();
-                let f = temp.iter().skip_while(|&t| *t == 0).count();
-                (temp, 4 - f as u8)
-            }).collect::<Vec<_>>();
-            match digits.len() {
-                19 => Ok(Digits::Short(DigitsRaw {
-					bin_pre: digits[0],
-					bin_pre_u8: digits_u8array[0],
-					hex_pre: digits[1],
-					hex_pre_u8: digits_u8array[1],
-					oct_pre: digits[2],
-					oct_pre_u8: digits_u8array[2],
-					digits: digits[3..].to_vec().try_into().unwrap(),
-					u8arrays: digits_u8array[3..].to_vec().try_into().unwrap(),
-				})),
-                25 => Ok(Digits::Long(DigitsRaw {
-					bin_pre: digits[0],
-					bin_pre_u8: digits_u8array[0],
-					hex_pre: digits[1],
-					hex_pre_u8: digits_u8array[1],
-					oct_pre: digits[2],
-					oct_pre_u8: digits_u8array[2],
-					digits: digits[3..].to_vec().try_into().unwrap(),
-					u8arrays: digits_u8array[3..].to_vec().try_into().unwrap(),
-				})),
-                t => Err(format!("Digits must be either 19 or 25 long. Found {}", t))
+    fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
+        let ln = s.next("digits", "digits line")?;
+        let mut digits = Vec::with_capacity(26);
+        for d in ln.split_whitespace() {
+            if let Ok(d) = char::from_str(d) {
+                digits.push(d)
+            } else {
+                return Err(s.error("digits", "single character", Some(d)))
             }
-        } else {
-            Err(format!("Expected digits line"))
         }
+        let digits_u8array = digits.iter().map(|t| {
+            let mut encoded = [0; 4];
+            let f = t.encode_utf8(&mut encoded).len();
+            let mut temp = [0; 4];
+            temp[4 - f..].copy_from_slice(&encoded[..f]);
+            (temp, 4 - f as u8)
+        }).collect::<Vec<_>>();
+        let found = digits.len().to_string();
+        match digits.len() {
+            19 => DigitsRaw::new(&digits, &digits_u8array).map(Digits::Short),
+            25 => DigitsRaw::new(&digits, &digits_u8array).map(Digits::Long),
+            _ => None
+        }.ok_or_else(|| s.error("digits", "19 or 25 digits", Some(&*found)))
+    }
+}
+
+impl Serialize for Digits {
+    fn serialize(&self, out: &mut String) {
+        let digits = match self {
+            Digits::Short(t) => [t.bin_pre, t.hex_pre, t.oct_pre].iter().chain(t.digits.iter()).map(char::to_string).collect::<Vec<_>>(),
+            Digits::Long(t) => [t.bin_pre, t.hex_pre, t.oct_pre].iter().chain(t.digits.iter()).map(char::to_string).collect::<Vec<_>>(),
+        };
+        words(out, digits.iter().map(String::as_str))
     }
 }
 
 impl Digits {
-    /// Separate a matched digit (`Vec<u8>`) into it's component digit units.
+    /// Separate a matched digit (`Vec<u8>`) into it's component digit units. `_` digit separators
+    /// are skipped.
     ///
     /// For example, if we had `vec![50, 48, 51]` for 203, this would separate that into `vec![2, 0, 3]`
     pub fn separate(&self, base: usize, matcher: Vec<u8>) -> Vec<u8> {
@@ -104,14 +110,17 @@ impl Digits {
                 t.u8arrays[..base].iter().map(|(t, s)| (*t, *s as usize)).enumerate().collect::<Vec<_>>()
             }
         };
-        let mut pos = pos.iter().cycle();
         let mut n = 0;
         let mut out = Vec::new();
         while n < matcher.len() {
-            if let Some((v, (_, s))) = pos.find(|(_, (t, s))| &t[*s..] == &matcher[n..n + 4 - *s]) {
+            if matcher[n] == b'_' {
+                n += 1;
+                continue
+            }
+            if let Some((v, (_, s))) = pos.iter().find(|(_, (t, s))| matcher.get(n..n + 4 - *s) == Some(&t[*s..])) {
                 out.push(*v as u8);
                 n += 4 - s;
-            } else { unreachable!("Unable to match digit bytes with digits:\n{:?}\n{:?}", matcher, pos.collect::<Vec<_>>()) }
+            } else { unreachable!("Unable to match digit bytes with digits:\n{:?}\n{:?}", matcher, pos) }
         }
         out
     }
@@ -120,7 +129,8 @@ impl Digits {
 /// Raw digits
 ///
 /// Contains the binary, hex, and octal prefixes; as well as digits.
-/// u8 array versions (chars cast to `u32` with their length in bytes) are also given
+/// u8 array versions (UTF-8 encoded chars right-aligned in a `[u8; 4]` with the number of leading
+/// padding bytes) are also given
 #[derive(Clone)]
 pub struct DigitsRaw<const N: usize> {
 	/// Binary prefix
@@ -141,6 +151,23 @@ pub struct DigitsRaw<const N: usize> {
 	pub u8arrays: [([u8; 4], u8); N],
 }
 
+impl<const N: usize> DigitsRaw<N> {
+	/// Make a new instance from the three prefixes followed by `N` digits. Returns `None` if the
+	/// number of digits given is not `N`
+	fn new(digits: &[char], u8arrays: &[([u8; 4], u8)]) -> Option<Self> {
+		Some(Self {
+			bin_pre: *digits.first()?,
+			bin_pre_u8: *u8arrays.first()?,
+			hex_pre: *digits.get(1)?,
+			hex_pre_u8: *u8arrays.get(1)?,
+			oct_pre: *digits.get(2)?,
+			oct_pre_u8: *u8arrays.get(2)?,
+			digits: digits.get(3..)?.try_into().ok()?,
+			u8arrays: u8arrays.get(3..)?.try_into().ok()?,
+		})
+	}
+}
+
 /// All the control keywords
 #[derive(Copy, Clone)]
 pub struct ControlKwds<'a> {
@@ -194,11 +221,14 @@ pub struct ControlKwds<'a> {
 }
 
 impl<'a> Deserialize<'a> for ControlKwds<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-		let line = s.next().expect("Expected control keywords line. Found nothing");
-		let mut line = line.split_whitespace();
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
+		let mut line = s.words("control keywords")?;
 		macro_rules! fields {
-		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: line.next().expect(concat!("Expected ", stringify!($name), ", found nothing"))),* })};
+		    ($($name:ident),*$(,)?) => {{
+				let out = Self { $($name: line.next(&stringify!($name)[2..])?),* };
+				line.end()?;
+				Ok(out)
+			}};
 		}
 		fields!(
 			k_set, k_and, k_or, k_not, k_if, k_else, k_match, k_repeat, k_for, k_in,
@@ -207,6 +237,12 @@ impl<'a> Deserialize<'a> for ControlKwds<'a> {
 	}
 }
 
+impl Serialize for ControlKwds<'_> {
+	fn serialize(&self, out: &mut String) {
+		words(out, *self)
+	}
+}
+
 impl<'a> IntoIterator for ControlKwds<'a> {
 	type Item = &'a str;
 	type IntoIter = <Vec<&'a str> as IntoIterator>::IntoIter;
@@ -247,16 +283,25 @@ pub struct TypeKwds<'a> {
 }
 
 impl<'a> Deserialize<'a> for TypeKwds<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-		let line = s.next().expect("Expected control keywords line. Found nothing");
-		let mut line = line.split_whitespace();
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
+		let mut line = s.words("type keywords")?;
 		macro_rules! fields {
-		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: line.next().expect(concat!("Expected ", stringify!($name), ", found nothing"))),* })};
+		    ($($name:ident),*$(,)?) => {{
+				let out = Self { $($name: line.next(&stringify!($name)[2..])?),* };
+				line.end()?;
+				Ok(out)
+			}};
 		}
 		fields!(k_struct, k_properties, k_enum, k_variants, k_self, k_Self, k_extension, k_extend, k_const)
 	}
 }
 
+impl Serialize for TypeKwds<'_> {
+	fn serialize(&self, out: &mut String) {
+		words(out, *self)
+	}
+}
+
 impl<'a> IntoIterator for TypeKwds<'a> {
 	type Item = &'a str;
 	type IntoIter = <Vec<&'a str> as IntoIterator>::IntoIter;
@@ -294,16 +339,25 @@ pub struct PrimitiveKwds<'a> {
 }
 
 impl<'a> Deserialize<'a> for PrimitiveKwds<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-		let line = s.next().expect("Expected control keywords line. Found nothing");
-		let mut line = line.split_whitespace();
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
+		let mut line = s.words("primitive keywords")?;
 		macro_rules! fields {
-		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: line.next().expect(concat!("Expected ", stringify!($name), ", found nothing"))),* })};
+		    ($($name:ident),*$(,)?) => {{
+				let out = Self { $($name: line.next(&stringify!($name)[2..])?),* };
+				line.end()?;
+				Ok(out)
+			}};
 		}
 		fields!(k_int, k_uint, k_dint, k_udint, k_float, k_bfloat, k_str, k_char, k_list, k_bool)
 	}
 }
 
+impl Serialize for PrimitiveKwds<'_> {
+	fn serialize(&self, out: &mut String) {
+		words(out, *self)
+	}
+}
+
 impl<'a> IntoIterator for PrimitiveKwds<'a> {
 	type Item = &'a str;
 	type IntoIter = <Vec<&'a str> as IntoIterator>::IntoIter;
@@ -326,16 +380,25 @@ pub struct BoolKwds<'a> {
 }
 
 impl<'a> Deserialize<'a> for BoolKwds<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-		let line = s.next().expect("Expected control keywords line. Found nothing");
-		let mut line = line.split_whitespace();
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
+		let mut line = s.words("bool keywords")?;
 		macro_rules! fields {
-		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: line.next().expect(concat!("Expected ", stringify!($name), ", found nothing"))),* })};
+		    ($($name:ident),*$(,)?) => {{
+				let out = Self { $($name: line.next(&stringify!($name)[2..])?),* };
+				line.end()?;
+				Ok(out)
+			}};
 		}
 		fields!(k_true, k_false)
 	}
 }
 
+impl Serialize for BoolKwds<'_> {
+	fn serialize(&self, out: &mut String) {
+		words(out, *self)
+	}
+}
+
 impl<'a> IntoIterator for BoolKwds<'a> {
 	type Item = &'a str;
 	type IntoIter = <Vec<&'a str> as IntoIterator>::IntoIter;
@@ -435,11 +498,14 @@ impl ManifestKwds<'_> {
 }
 
 impl<'a> Deserialize<'a> for ManifestKwds<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-		let line = s.next().expect("Expected control keywords line. Found nothing");
-		let mut line = line.split_whitespace();
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
+		let mut line = s.words("manifest keys")?;
 		macro_rules! fields {
-		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: line.next().expect(concat!("Expected ", stringify!($name), ", found nothing"))),* })};
+		    ($($name:ident),*$(,)?) => {{
+				let out = Self { $($name: line.next(&stringify!($name)[2..])?),* };
+				line.end()?;
+				Ok(out)
+			}};
 		}
 		fields!(
 			k_package, k_name, k_src, k_tests, k_benches, k_type, k_lib, k_app, k_version,
@@ -450,6 +516,12 @@ impl<'a> Deserialize<'a> for ManifestKwds<'a> {
 	}
 }
 
+impl Serialize for ManifestKwds<'_> {
+	fn serialize(&self, out: &mut String) {
+		words(out, *self)
+	}
+}
+
 impl<'a> IntoIterator for ManifestKwds<'a> {
 	type Item = &'a str;
 	type IntoIter = <Vec<&'a str> as IntoIterator>::IntoIter;
@@ -491,12 +563,24 @@ pub struct CompileKwds<'a> {
 }
 
 impl<'a> Deserialize<'a> for CompileKwds<'a> {
-	fn deserialize<T: Iterator<Item=&'a str>>(s: &mut T) -> Result<Self, String> where Self: Sized {
-		let line = s.next().expect("Expected control keywords line. Found nothing");
-		let mut line = line.split_whitespace();
+	fn deserialize(s: &mut Lines<'a>) -> Result<Self, FcklError> where Self: Sized {
+		let mut line = s.words("compile words")?;
 		macro_rules! fields {
-		    ($($name:ident),*$(,)?) => {Ok(Self { $($name: line.next().expect(concat!("Expected ", stringify!($name), ", found nothing"))),* })};
+		    ($($name:ident),*$(,)?) => {{
+				let out = Self { $($name: line.next(&stringify!($name)[2..])?),* };
+				line.end()?;
+				Ok(out)
+			}};
 		}
 		fields!(k_Compiling, k_Building, k_Built, k_Linking, k_Emitted, k_Error, k_errors, k_Warning, k_warnings)
 	}
 }
+
+impl Serialize for CompileKwds<'_> {
+	fn serialize(&self, out: &mut String) {
+		words(out, [
+			self.k_Compiling, self.k_Building, self.k_Built, self.k_Linking, self.k_Emitted,
+			self.k_Error, self.k_errors, self.k_Warning, self.k_warnings
+		])
+	}
+}
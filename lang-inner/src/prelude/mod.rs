@@ -26,7 +26,8 @@ pub use err_warn::*;
 use std::fmt::Formatter;
 use std::ops::Index;
 
-use crate::de::Deserialize;
+use crate::de::{Deserialize, FcklError, Lines};
+use crate::se::Serialize;
 
 /// # Language struct
 ///
@@ -35,6 +36,9 @@ use crate::de::Deserialize;
 pub struct LanguageRaw<'a> {
     /// Language name: `(full name, language code)`
     pub name: (&'a str, &'a str),
+    /// Whitespace separated aliases the language can also be found by. See
+    /// [`aliases`](Self::aliases)
+    pub aliases: &'a str,
     /// Is the language left-to-right or right-to-left
     pub left_right: bool,
     /// Keywords for the language
@@ -66,11 +70,38 @@ impl std::fmt::Debug for LanguageRaw<'_> {
 impl<'a> LanguageRaw<'a> {
     /// Try to deserialize an fckl file into a [`LanguageRaw`] struct
     ///
-    /// This is a public wrapper around a call to the [`Deserialize::deserialize`] function
-    pub fn from_text(s: &'a str) -> Result<Self, String> {
-        let mut lines = s.lines();
+    /// This is a public wrapper around a call to the [`Deserialize::deserialize`] function. Returns
+    /// an [`FcklError`] with the line and field that failed if the file is malformed
+    pub fn from_text(s: &'a str) -> Result<Self, FcklError> {
+        let mut lines = Lines::new(s);
         Deserialize::deserialize(&mut lines)
     }
+
+    /// Iterate over the language aliases
+    pub fn aliases(&self) -> impl Iterator<Item = &'a str> {
+        self.aliases.split_whitespace()
+    }
+
+    /// Check if a name refers to this language
+    ///
+    /// This ignores case and matches the language code, full name, or any of the
+    /// [aliases](Self::aliases)
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        [self.name.1, self.name.0].into_iter()
+            .chain(self.aliases())
+            .any(|t| t.to_lowercase() == name)
+    }
+
+    /// Serialize the language into canonical fckl text
+    ///
+    /// This writes the same line layout [`from_text`](Self::from_text) reads, so
+    /// `LanguageRaw::from_text(&l.to_fckl())` gives back the same language
+    pub fn to_fckl(&self) -> String {
+        let mut out = String::new();
+        self.serialize(&mut out);
+        out
+    }
 }
 
 /// Table element trait
@@ -0,0 +1,39 @@
+use crate::*;
+
+/// Inverse of [`Deserialize`](crate::de::Deserialize). Writes the exact line layout the
+/// deserializer expects
+pub(crate) trait Serialize {
+    /// Write `self` to `out` as fckl lines. Each line ends with a newline
+    fn serialize(&self, out: &mut String);
+}
+
+/// Write a single line of space separated words
+pub(crate) fn words<'a, T: IntoIterator<Item = &'a str>>(out: &mut String, words: T) {
+    for (i, w) in words.into_iter().enumerate() {
+        if i != 0 { out.push(' ') }
+        out.push_str(w)
+    }
+    out.push('\n')
+}
+
+/// Write a single line
+pub(crate) fn line(out: &mut String, line: &str) {
+    out.push_str(line);
+    out.push('\n')
+}
+
+impl Serialize for LanguageRaw<'_> {
+    fn serialize(&self, out: &mut String) {
+        words(out, [if self.left_right { "{" } else { "}" }, self.name.0, self.name.1].into_iter().chain(self.aliases()));
+        self.keywords.serialize(out);
+        self.messages.serialize(out);
+    }
+}
+
+impl Serialize for Messages<'_> {
+    fn serialize(&self, out: &mut String) {
+        self.errors.serialize(out);
+        self.warnings.serialize(out);
+        self.cli_keywords.serialize(out);
+    }
+}
@@ -5,20 +5,36 @@
 //! [multi-byte variant](multi_bytes)
 //!
 //! Matches here will give a `tt=1` and:
-//! - `td=0` for base 10
-//! - `td=1` for base 2
-//! - `td=2` for base 16
-//! - `td=3` for base 8
+//! - `td=2` for base 10
+//! - `td=3` for base 2
+//! - `td=4` for base 16
+//! - `td=5` for base 8
+//! - `td=6` for floats
+//!
+//! Floats use `.` as the decimal point and `e` or `E` followed by an optionally signed exponent.
+//! These are not localised, unlike the digits themselves
+//!
+//! A `_` digit separator can be put between any two digits, such as `1_000` or `0xFF_FF`. Each
+//! number state has its own separator state that only moves back on a digit, so a separator can't
+//! start, end, or be repeated in a number. Separators are removed by [`Digits::separate`]
 
 use itertools::Itertools;
 use crate::Digits;
 
+/// Digit separator `_`
+const SEPARATOR: u8 = 95;
+
+/// Encode the given digits into the maps
+///
+/// Returns any rows that were added as intermediate states from the initial row (`q0`). These
+/// are rows part way through a multi-byte digit and need to be merged with the identifier row
 pub fn encode(
 	digits: &Digits,
 	map1: &mut Vec<[u16; 256]>,
 	map2: &mut Vec<[u8; 256]>,
 	map3: &mut Vec<[u8; 256]>
-) {
+) -> Vec<usize> {
+	let mut q0_rows = Vec::new();
 	match digits {
 		Digits::Short(t) => if t.u8arrays.iter().all(|(_, t)| *t == 3) && t.bin_pre_u8.1 == 3 && t.hex_pre_u8.1 == 3 && t.oct_pre_u8.1 == 3 {
 			single_bytes(
@@ -28,9 +44,9 @@ pub fn encode(
 			);
 		} else {
 			multi_bytes(
-				t.u8arrays.map(|(t, _)| t).to_vec(),
-				vec![t.bin_pre_u8.0, t.hex_pre_u8.0, t.oct_pre_u8.0],
-				map1, map2, map3
+				t.u8arrays.iter().map(|(t, s)| &t[*s as usize..]).collect(),
+				[t.bin_pre_u8, t.hex_pre_u8, t.oct_pre_u8].iter().map(|(t, s)| t[*s as usize..].to_vec()).collect(),
+				map1, map2, map3, &mut q0_rows
 			);
 		}
 		Digits::Long(t) => if t.u8arrays.iter().all(|(_, t)| *t == 3) && t.bin_pre_u8.1 == 3 && t.hex_pre_u8.1 == 3 && t.oct_pre_u8.1 == 3 {
@@ -41,12 +57,13 @@ pub fn encode(
 			);
 		} else {
 			multi_bytes_long(
-				t.u8arrays.map(|(t, _)| t).to_vec(),
-				vec![t.bin_pre_u8.0, t.hex_pre_u8.0, t.oct_pre_u8.0],
-				map1, map2, map3
+				t.u8arrays.iter().map(|(t, s)| &t[*s as usize..]).collect(),
+				[t.bin_pre_u8, t.hex_pre_u8, t.oct_pre_u8].iter().map(|(t, s)| t[*s as usize..].to_vec()).collect(),
+				map1, map2, map3, &mut q0_rows
 			);
 		}
 	}
+	q0_rows
 }
 
 /// Single byte numeral table insertion
@@ -54,12 +71,12 @@ pub fn encode(
 /// This inserts rows to accept all valid digits. Should be used
 /// if possible because it's much faster than the [multibyte variant](multi_bytes)
 ///
-/// This returns two `usize`s that are the h0 and h row. This is required to be used by the
+/// This returns the h0, h, and hex separator rows. This is required to be used by the
 /// [long variant](single_bytes_long)
 fn single_bytes(
 	digits: Vec<u8>, prefixes: Vec<u8>,
 	map1: &mut Vec<[u16; 256]>, map2: &mut Vec<[u8; 256]>, map3: &mut Vec<[u8; 256]>
-) -> (usize, usize) {
+) -> [usize; 3] {
 	// d0
 	let zero_row = map1.len();
     map1.push([0; 256]);
@@ -98,23 +115,58 @@ fn single_bytes(
 	map2[digit_row][digits[0] as usize] = 1;
 	map3[digit_row][digits[0] as usize] = 2;
 	
-	// f
-	let float_row = map1.len();
-    map1.push([0; 256]);
-    map2.push([0; 256]);
-    map3.push([0; 256]);
-	// {d0,d} --.--> f
-	map1[zero_row][46] = float_row as u16;
-	map1[digit_row][46] = float_row as u16;
+	// f0
+	let point_row = new_row(map1, map2, map3);
+	// {d0,d} --.--> f0
+	map1[zero_row][46] = point_row as u16;
+	map1[digit_row][46] = point_row as u16;
 	map2[zero_row][46] = 1;
 	map3[zero_row][46] = 6;
 	map2[digit_row][46] = 1;
 	map3[digit_row][46] = 6;
-	// f --0..9--> f
+	// f
+	let float_row = new_row(map1, map2, map3);
+	// {f0,f} --0..9--> f
+	for &n in digits[0..10].iter() {
+		for row in [point_row, float_row] {
+			map1[row][n as usize] = float_row as u16;
+			map2[row][n as usize] = 1;
+			map3[row][n as usize] = 6;
+		}
+	}
+	
+	// e0
+	let exp_init = map1.len();
+    map1.push([0; 256]);
+    map2.push([0; 256]);
+    map3.push([0; 256]);
+	// es
+	let exp_sign = map1.len();
+    map1.push([0; 256]);
+    map2.push([0; 256]);
+    map3.push([0; 256]);
+	// e
+	let exp = map1.len();
+    map1.push([0; 256]);
+    map2.push([0; 256]);
+    map3.push([0; 256]);
+	for b in [69, 101] {
+		// {d0,d,f0,f} --e,E--> e0
+		for row in [zero_row, digit_row, point_row, float_row] {
+			map1[row][b] = exp_init as u16;
+		}
+	}
+	for b in [43, 45] {
+		// e0 --+,- --> es
+		map1[exp_init][b] = exp_sign as u16;
+	}
+	// {e0,es,e} --0..9--> e
 	for &n in digits[0..10].iter() {
-		map1[float_row][n as usize] = float_row as u16;
-		map2[float_row][n as usize] = 1;
-		map3[float_row][n as usize] = 6;
+		for row in [exp_init, exp_sign, exp] {
+			map1[row][n as usize] = exp as u16;
+			map2[row][n as usize] = 1;
+			map3[row][n as usize] = 6;
+		}
 	}
 	
 	// b0
@@ -182,7 +234,22 @@ fn single_bytes(
 		map2[oct][n as usize] = 1;
 		map3[oct][n as usize] = 5;
 	}
-	(hex_init, hex)
+	
+	// {d0,d} --_--> ds --0..9--> d and {f,e,b,h,o} --_--> {fs,es,bs,hs,os} --digit--> self
+	let mut hex_sep = 0;
+	for (from, to, len, td) in [(&[zero_row, digit_row][..], digit_row, 10, 2), (&[float_row], float_row, 10, 6), (&[exp], exp, 10, 6), (&[bin], bin, 2, 3), (&[hex], hex, 16, 4), (&[oct], oct, 8, 5)] {
+		let sep = new_row(map1, map2, map3);
+		for &row in from {
+			map1[row][SEPARATOR as usize] = sep as u16;
+		}
+		for &n in digits[0..len].iter() {
+			map1[sep][n as usize] = to as u16;
+			map2[sep][n as usize] = 1;
+			map3[sep][n as usize] = td;
+		}
+		if to == hex { hex_sep = sep }
+	}
+	[hex_init, hex, hex_sep]
 }
 
 /// [Long](lang_inner::Digits::Long) digit version of [`single_bytes`]
@@ -192,34 +259,178 @@ fn single_bytes_long(
 	map2: &mut Vec<[u8; 256]>,
 	map3: &mut Vec<[u8; 256]>,
 ) {
-	let (hex_init, hex) = single_bytes(digits[..16].to_vec(), prefixes, map1, map2, map3);
+	let rows = single_bytes(digits[..16].to_vec(), prefixes, map1, map2, map3);
 	for &n in digits[16..].iter() {
-		// {h0,h} --A..F--> h
-		map1[hex_init][n as usize] = hex as u16;
-		map2[hex_init][n as usize] = 1;
-		map3[hex_init][n as usize] = 4;
-		map1[hex][n as usize] = hex as u16;
-		map2[hex][n as usize] = 1;
-		map3[hex][n as usize] = 4;
+		// {h0,h,hs} --A..F--> h
+		for row in rows {
+			map1[row][n as usize] = rows[1] as u16;
+			map2[row][n as usize] = 1;
+			map3[row][n as usize] = 4;
+		}
+	}
+}
+
+/// Add a new empty row to each of the maps and return its index
+fn new_row(map1: &mut Vec<[u16; 256]>, map2: &mut Vec<[u8; 256]>, map3: &mut Vec<[u8; 256]>) -> usize {
+	let row = map1.len();
+	map1.push([0; 256]);
+	map2.push([0; 256]);
+	map3.push([0; 256]);
+	row
+}
+
+/// Insert a transition `from --c--> to` for a multi-byte character `c`
+///
+/// Every byte except the last moves through an intermediate row. Intermediate rows are shared
+/// between characters with common leading bytes from the same `from` row, which is always valid
+/// since UTF-8 encodings are prefix free. `tt` and `td` are only set on the last byte since the
+/// token cannot end part way through a character.
+///
+/// Any new intermediate rows are pushed onto `new_rows`
+fn insert_char(
+	from: usize, to: usize, c: &[u8], tt: u8, td: u8,
+	(map1, map2, map3): (&mut Vec<[u16; 256]>, &mut Vec<[u8; 256]>, &mut Vec<[u8; 256]>),
+	new_rows: &mut Vec<usize>,
+) {
+	let (last, split) = c.split_last().expect("Empty character given");
+	let mut row = from;
+	for &b in split {
+		if map1[row][b as usize] == 0 {
+			let next = new_row(map1, map2, map3);
+			map1[row][b as usize] = next as u16;
+			new_rows.push(next);
+		}
+		row = map1[row][b as usize] as usize
 	}
+	map1[row][*last as usize] = to as u16;
+	map2[row][*last as usize] = tt;
+	map3[row][*last as usize] = td;
 }
 
-/// todo
-pub fn multi_bytes(
-	digits: Vec<[u8; 4]>, prefixes: Vec<[u8; 4]>,
+/// Multi-byte numeral table insertion
+///
+/// This builds the same states as [`single_bytes`] but with each digit and prefix being a UTF-8
+/// encoded byte sequence. Any intermediate rows from `q0` are pushed onto `q0_rows`
+///
+/// This returns the h0, h, and hex separator rows. This is required to be used by the
+/// [long variant](multi_bytes_long)
+fn multi_bytes(
+	digits: Vec<&[u8]>, prefixes: Vec<Vec<u8>>,
 	map1: &mut Vec<[u16; 256]>,
 	map2: &mut Vec<[u8; 256]>,
 	map3: &mut Vec<[u8; 256]>,
-) {
-	todo!()
+	q0_rows: &mut Vec<usize>,
+) -> [usize; 3] {
+	// rows reached from a state other than q0 are not identifier rows
+	let mut other_rows = Vec::new();
+	macro_rules! insert {
+	    ($from:expr, $to:expr, $c:expr, $tt:expr, $td:expr) => {
+			if $from == 0 {
+				insert_char($from, $to, $c, $tt, $td, (&mut *map1, &mut *map2, &mut *map3), q0_rows)
+			} else {
+				insert_char($from, $to, $c, $tt, $td, (&mut *map1, &mut *map2, &mut *map3), &mut other_rows)
+			}
+		};
+	}
+	
+	let zero_row = new_row(map1, map2, map3);
+	let digit_row = new_row(map1, map2, map3);
+	// q0 --0--> d0
+	insert!(0, zero_row, digits[0], 1, 2);
+	// d0 --0--> d
+	insert!(zero_row, digit_row, digits[0], 1, 2);
+	for &n in digits[1..10].iter() {
+		// q0 --1..9--> d
+		insert!(0, digit_row, n, 1, 2);
+		// d --1..9--> d
+		insert!(digit_row, digit_row, n, 1, 2);
+		// d0 --1..9--> d
+		insert!(zero_row, digit_row, n, 1, 2);
+	}
+	// d --0--> d
+	insert!(digit_row, digit_row, digits[0], 1, 2);
+	
+	// f0
+	let point_row = new_row(map1, map2, map3);
+	// {d0,d} --.--> f0
+	insert!(zero_row, point_row, &[46], 1, 6);
+	insert!(digit_row, point_row, &[46], 1, 6);
+	// f
+	let float_row = new_row(map1, map2, map3);
+	// {f0,f} --0..9--> f
+	for &n in digits[0..10].iter() {
+		insert!(point_row, float_row, n, 1, 6);
+		insert!(float_row, float_row, n, 1, 6);
+	}
+	
+	let exp_init = new_row(map1, map2, map3);
+	let exp_sign = new_row(map1, map2, map3);
+	let exp = new_row(map1, map2, map3);
+	for b in [69, 101] {
+		// {d0,d,f0,f} --e,E--> e0
+		for row in [zero_row, digit_row, point_row, float_row] {
+			insert!(row, exp_init, &[b], 0, 0);
+		}
+	}
+	for b in [43, 45] {
+		// e0 --+,- --> es
+		insert!(exp_init, exp_sign, &[b], 0, 0);
+	}
+	// {e0,es,e} --0..9--> e
+	for &n in digits[0..10].iter() {
+		for row in [exp_init, exp_sign, exp] {
+			insert!(row, exp, n, 1, 6);
+		}
+	}
+	
+	// {d0,d} --_--> ds --0..9--> d and {f,e} --_--> {fs,es} --0..9--> self
+	for (from, to, td) in [(&[zero_row, digit_row][..], digit_row, 2), (&[float_row], float_row, 6), (&[exp], exp, 6)] {
+		let sep = new_row(map1, map2, map3);
+		for &row in from {
+			insert!(row, sep, &[SEPARATOR], 0, 0);
+		}
+		for &n in digits[0..10].iter() {
+			insert!(sep, to, n, 1, td);
+		}
+	}
+	
+	// {b0,b,bs,h0,h,hs,o0,o,os}
+	let mut out = [0; 3];
+	for (prefix, (len, td)) in prefixes.iter().zip([(2, 3), (16, 4), (8, 5)]) {
+		let init = new_row(map1, map2, map3);
+		let row = new_row(map1, map2, map3);
+		// d0 --prefix--> init
+		insert!(zero_row, init, prefix, 0, 0);
+		// {init,row} --digit--> row
+		for &n in digits[0..len].iter() {
+			insert!(init, row, n, 1, td);
+			insert!(row, row, n, 1, td);
+		}
+		// row --_--> sep --digit--> row
+		let sep = new_row(map1, map2, map3);
+		insert!(row, sep, &[SEPARATOR], 0, 0);
+		for &n in digits[0..len].iter() {
+			insert!(sep, row, n, 1, td);
+		}
+		if len == 16 { out = [init, row, sep] }
+	}
+	out
 }
 
-/// todo
-pub fn multi_bytes_long(
-	digits: Vec<[u8; 4]>, prefixes: Vec<[u8; 4]>,
+/// [Long](lang_inner::Digits::Long) digit version of [`multi_bytes`]
+fn multi_bytes_long(
+	digits: Vec<&[u8]>, prefixes: Vec<Vec<u8>>,
 	map1: &mut Vec<[u16; 256]>,
 	map2: &mut Vec<[u8; 256]>,
 	map3: &mut Vec<[u8; 256]>,
+	q0_rows: &mut Vec<usize>,
 ) {
-	todo!()
+	let rows = multi_bytes(digits[..16].to_vec(), prefixes, map1, map2, map3, q0_rows);
+	let mut other_rows = Vec::new();
+	for &n in digits[16..].iter() {
+		// {h0,h,hs} --A..F--> h
+		for row in rows {
+			insert_char(row, rows[1], n, 1, 4, (&mut *map1, &mut *map2, &mut *map3), &mut other_rows);
+		}
+	}
 }
@@ -0,0 +1,170 @@
+//! # Identifier recognition insertion
+//!
+//! Identifiers start with an `XID_Start` character or `_`, followed by any number of
+//! `XID_Continue` characters. Multi-byte characters are encoded as intermediate rows, one for each
+//! byte before the last, and the last byte moves to the identifier row. Intermediate rows with the
+//! same transitions are shared, so whole blocks of characters only need a few rows. These rows are
+//! the same for every language, so they are only built once and copied into each table
+//!
+//! Matches here will give a `tt=9`
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// Token type for identifiers
+const IDENT_TT: u8 = 9;
+
+/// Row with its transition, token type, and token data tables
+pub(crate) type Row = ([u16; 256], [u8; 256], [u8; 256]);
+
+/// Transition to the identifier row in [`Rows`]
+const CACHED_IDENT: u16 = u16::MAX;
+
+/// Identifier rows built by the first call to [`encode`]
+static ROWS: OnceLock<Rows> = OnceLock::new();
+
+/// Identifier rows not yet placed in a table. Transitions to intermediate rows are indices into
+/// [`rows`](Self::rows) plus one, and transitions to the identifier row are [`CACHED_IDENT`]
+struct Rows {
+	/// Row accepting the first character of an identifier
+	start: Row,
+	/// Identifier row accepting the rest of an identifier
+	cont: Row,
+	/// Intermediate rows
+	rows: Vec<Row>,
+}
+
+impl Rows {
+	/// Build the identifier rows
+	fn new() -> Self {
+		// placeholder row so no intermediate row is at 0, which means no transition
+		let (mut map1, mut map2, mut map3) = (vec![[0; 256]], vec![[0; 256]], vec![[0; 256]]);
+		let mut rows = HashMap::new();
+		let start = Trie::new(|c| c == '_' || is_xid_start(c));
+		let start = start.row(CACHED_IDENT as usize, (&mut map1, &mut map2, &mut map3), &mut rows);
+		let cont = Trie::new(is_xid_continue);
+		let cont = cont.row(CACHED_IDENT as usize, (&mut map1, &mut map2, &mut map3), &mut rows);
+		let rows = map1.into_iter().zip(map2).zip(map3).skip(1).map(|((t1, t2), t3)| (t1, t2, t3)).collect();
+		Self { start, cont, rows }
+	}
+}
+
+/// Move the transitions of a row from [`Rows`] to a table where the intermediate rows start at
+/// `base`
+fn relocate(row: &Row, ident_row: usize, base: usize) -> Row {
+	let mut out = *row;
+	for t in out.0.iter_mut() {
+		*t = match *t {
+			0 => 0,
+			CACHED_IDENT => ident_row as u16,
+			t => (base + t as usize - 1) as u16,
+		}
+	}
+	out
+}
+
+/// Byte trie of UTF-8 encoded characters. `None` marks the last byte of a character
+#[derive(Default)]
+struct Trie(HashMap<u8, Option<Trie>>);
+
+impl Trie {
+	/// Make a trie of all characters matching `f`
+	fn new(f: impl Fn(char) -> bool) -> Self {
+		let mut out = Self::default();
+		for c in (0..=char::MAX as u32).filter_map(char::from_u32).filter(|c| f(*c)) {
+			let mut buf = [0; 4];
+			let (last, split) = c.encode_utf8(&mut buf).as_bytes().split_last().unwrap();
+			let mut node = &mut out;
+			for b in split {
+				node = node.0.entry(*b).or_insert_with(|| Some(Self::default())).as_mut().unwrap()
+			}
+			node.0.insert(*last, None);
+		}
+		out
+	}
+
+	/// Make the row for this trie. Intermediate rows are added to the maps, reusing rows with the
+	/// same transitions from `rows`
+	fn row(
+		&self, ident_row: usize,
+		maps: (&mut Vec<[u16; 256]>, &mut Vec<[u8; 256]>, &mut Vec<[u8; 256]>),
+		rows: &mut HashMap<Vec<(u8, u16)>, usize>,
+	) -> Row {
+		let (map1, map2, map3) = maps;
+		let mut out = ([0; 256], [0; 256], [0; 256]);
+		for (b, next) in self.0.iter() {
+			match next {
+				None => {
+					out.0[*b as usize] = ident_row as u16;
+					out.1[*b as usize] = IDENT_TT;
+				}
+				Some(next) => {
+					let row = next.row(ident_row, (&mut *map1, &mut *map2, &mut *map3), rows);
+					let key = (0..256).filter(|t| row.0[*t] != 0).map(|t| (t as u8, row.0[t])).collect::<Vec<_>>();
+					let index = *rows.entry(key).or_insert_with(|| {
+						map1.push(row.0);
+						map2.push(row.1);
+						map3.push(row.2);
+						map1.len() - 1
+					});
+					out.0[*b as usize] = index as u16;
+				}
+			}
+		}
+		out
+	}
+}
+
+/// Encode the identifier rows into the maps
+///
+/// The identifier row is set to accept `XID_Continue` characters. The returned row accepts the
+/// first character of an identifier and is merged into `q0` once all other tokens have been added.
+/// It is not added to the maps
+pub(crate) fn encode(
+	ident_row: usize,
+	map1: &mut Vec<[u16; 256]>,
+	map2: &mut Vec<[u8; 256]>,
+	map3: &mut Vec<[u8; 256]>,
+) -> Row {
+	let cached = ROWS.get_or_init(Rows::new);
+	let base = map1.len();
+	for row in &cached.rows {
+		let row = relocate(row, ident_row, base);
+		map1.push(row.0);
+		map2.push(row.1);
+		map3.push(row.2);
+	}
+	let cont = relocate(&cached.cont, ident_row, base);
+	map1[ident_row] = cont.0;
+	map2[ident_row] = cont.1;
+	map3[ident_row] = cont.2;
+	relocate(&cached.start, ident_row, base)
+}
+
+/// Merge a template row into a row. Transitions and token types missing from the row are copied
+/// from the template
+///
+/// If both have a transition part way through a character, and the row's transition is to a row
+/// in `owned`, that row is merged with the template's row for the same bytes. This is used for
+/// keywords and multi-byte digits sharing bytes with identifier characters
+pub(crate) fn merge(
+	row: usize, template: &Row, ident_row: usize, owned: &[usize],
+	map1: &mut Vec<[u16; 256]>,
+	map2: &mut Vec<[u8; 256]>,
+	map3: &mut Vec<[u8; 256]>,
+) {
+	for n in 0..256 {
+		if map2[row][n] == 0 {
+			map2[row][n] = template.1[n];
+			map3[row][n] = template.2[n];
+		}
+		let (next, t) = (map1[row][n] as usize, template.0[n] as usize);
+		if next == 0 {
+			map1[row][n] = t as u16
+		} else if t != 0 && t != ident_row && next != t && owned.contains(&next) {
+			let template = (map1[t], map2[t], map3[t]);
+			merge(next, &template, ident_row, owned, map1, map2, map3)
+		}
+	}
+}
@@ -3,6 +3,7 @@
 //! This module implements tabularization for [`LanguageRaw`](crate::prelude::LanguageRaw)
 
 mod digits;
+mod ident;
 mod prelude;
 
 pub use prelude::tabularize;
@@ -1,4 +1,4 @@
-use crate::tables::digits;
+use crate::tables::{digits, ident};
 use crate::LanguageRaw;
 
 pub(crate) const IDENT_ROW: usize = 1;
@@ -23,29 +23,20 @@ pub fn tabularize(l: &LanguageRaw<'_>) -> (Vec<[u16; 256]>, Vec<[u8; 256]>, Vec<
 		map3[0][93] = temp;
 	}
 
-	// clone map1[0] to be map1[IDENT_ROW]
-	let mut repeat_ident_row = [0; 256];
-	let mut repeat_ident_row_m2 = [0; 256];
-	for i in 0..256 {
-		if map2[0][i] == 0 {
-			if map1[0][i] == 0 { repeat_ident_row[i] = IDENT_ROW as u16; }
-			repeat_ident_row_m2[i] = 9;
-		}
-	}
-	for i in [9, 10, 32, 123, 125] {
-		repeat_ident_row[i] = 0;
-		repeat_ident_row_m2[i] = 0;
-	}
+	// identifier rows. The start row is merged into q0 after everything else has been added so that
+	// every other token takes priority
+	let start = ident::encode(IDENT_ROW, &mut map1, &mut map2, &mut map3);
 
-	digits::encode(&l.keywords.digits, &mut map1, &mut map2, &mut map3);
+	// multi-byte digits can add rows part way through a character from q0. These may also be part
+	// way through an identifier character so are merged with the identifier rows like keyword rows
+	let mut owned = digits::encode(&l.keywords.digits, &mut map1, &mut map2, &mut map3);
+	let mut boundaries = Vec::new();
 
     // for each keyword, follow it as far as the table currently has
     // if we need to add additional rows, we add them
     // when we finish, set the corresponding map2 and map3 values
     // these are guaranteed to be both zero since the language will have been checked for
     // collisions earlier on
-
-    let mut ident_rows: Vec<usize> = Vec::new();
     
     macro_rules! simple_map {
 		($f:expr, $($t: expr),*$(,)?) => {
@@ -53,23 +44,24 @@ pub fn tabularize(l: &LanguageRaw<'_>) -> (Vec<[u16; 256]>, Vec<[u8; 256]>, Vec<
 			simple_map!(@inner, $f, counter);
 			$(counter += 1; simple_map!(@inner, $t, counter);)*
 		};
-	    (@inner, $t: expr, $e: ident) => {
+	    (@inner, $t: expr, $e: expr) => {
 			for (td, kwd) in $t.into_iter().enumerate() {
 				let mut row_index = 0;
 				let (last, split) = kwd.as_bytes().split_last().unwrap();
-				for b in split {
+				for (n, b) in split.iter().enumerate() {
 					if map1[row_index][*b as usize] == 0 {
 						// insert a new row
 						map1[row_index][*b as usize] = map1.len() as u16;
 						row_index = map1.len();
-						ident_rows.push(row_index);
+						owned.push(row_index);
 						map1.push([0; 256]);
-						map2.push(repeat_ident_row_m2);
+						map2.push([0; 256]);
 						map3.push([0; 256]);
 					} else {
 						// otherwise move through the table
 						row_index = map1[row_index][*b as usize] as usize;
 					}
+					if kwd.is_char_boundary(n + 1) { boundaries.push(row_index) }
 				}
 				map2[row_index][*last as usize] = $e;
 				map3[row_index][*last as usize] = td as u8;
@@ -82,44 +74,17 @@ pub fn tabularize(l: &LanguageRaw<'_>) -> (Vec<[u16; 256]>, Vec<[u8; 256]>, Vec<
         l.keywords.type_kwds,
         l.keywords.builtins
     );
+	simple_map!(@inner, l.keywords.bool, 1);
 	
-	for (td, kwd) in l.keywords.bool.into_iter().enumerate() {
-		let mut row_index = 0;
-		let (last, split) = kwd.as_bytes().split_last().unwrap();
-		for b in split {
-			if map1[row_index][*b as usize] == 0 {
-				// insert a new row
-				map1[row_index][*b as usize] = map1.len() as u16;
-				row_index = map1.len();
-				ident_rows.push(row_index);
-				map1.push([0; 256]);
-				map2.push(repeat_ident_row_m2);
-				map3.push([0; 256]);
-			} else {
-				// otherwise move through the table
-				row_index = map1[row_index][*b as usize] as usize;
-			}
-		}
-		map2[row_index][*last as usize] = 1;
-		map3[row_index][*last as usize] = td as u8;
-	}
-	
-	for i in ident_rows {
-		// merge current row with ident_row with the current row taking priority
-		for n in 0..256 {
-			if map1[i][n] == 0 { map1[i][n] = repeat_ident_row[n] }
-		}
-	}
-	
-	map1[IDENT_ROW] = repeat_ident_row;
-	map2[IDENT_ROW] = repeat_ident_row_m2;
-	
-	for i in 0..256 {
-		if map2[0][i] == 0 {
-			if map1[0][i] == 0 { map1[0][i] = IDENT_ROW as u16; }
-			map2[0][i] = 9;
-		}
+	// merge the identifier rows with the current rows taking priority. Keyword rows at the end of a
+	// character can continue as an identifier, and q0 can start one
+	boundaries.sort_unstable();
+	boundaries.dedup();
+	let cont = (map1[IDENT_ROW], map2[IDENT_ROW], map3[IDENT_ROW]);
+	for i in boundaries {
+		ident::merge(i, &cont, IDENT_ROW, &owned, &mut map1, &mut map2, &mut map3);
 	}
+	ident::merge(0, &start, IDENT_ROW, &owned, &mut map1, &mut map2, &mut map3);
 	
 	map1[0][10] = 0;
 	for i in [9, 32, 123, 125] {
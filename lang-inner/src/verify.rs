@@ -1,20 +1,127 @@
 //! # Language verification
 //!
-//! Contains a [public function](LanguageRaw::is_invalid) to check if a language is valid by using a
-//! [private trait](Verification)
+//! Contains a [public function](LanguageRaw::verify) to check if a language is valid by using a
+//! [private trait](Verification). Each problem found is returned as a [`Finding`] with the fckl
+//! line it came from
 use crate::{CLIKeywords, Digits, Keywords, LanguageRaw, Messages};
-use itertools::Itertools;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 
 impl LanguageRaw<'_> {
-    /// Check if the language is valid
+    /// Verify the language
     ///
     /// 'Valid' is defined by the struct impl of the [`Verification`] trait. Here it checks that the
-    /// language code doesn't contain a '!' character.
+    /// language code and aliases don't contain a '!' character.
     ///
-    /// This calls [`Verification::is_invalid`] for [`Self::keywords`] and [`Self::messages`]
+    /// This calls [`Verification::verify`] for [`Self::keywords`] and [`Self::messages`]
+    pub fn verify(&self) -> Vec<Finding> {
+        let mut out = Vec::new();
+        for code in std::iter::once(self.name.1).chain(self.aliases()) {
+            if code.contains('!') {
+                out.push(Finding { line: 1, kind: FindingKind::InvalidCode(code.to_string()) })
+            }
+        }
+        out.extend(self.keywords.verify(2));
+        out.extend(self.messages.verify(9));
+        out
+    }
+
+    /// Check if the language is valid. See [`verify`](Self::verify)
     pub fn is_invalid(&self) -> bool {
-        self.name.1.contains("!") || self.keywords.is_invalid() || self.messages.is_invalid()
+        !self.verify().is_empty()
+    }
+}
+
+/// # Verification finding
+///
+/// A single problem found when verifying a language
+#[derive(Clone, PartialEq, Debug)]
+pub struct Finding {
+    /// fckl line the problem is on. Starts from 1
+    pub line: usize,
+    /// What the problem is
+    pub kind: FindingKind,
+}
+
+/// # Verification finding kind
+#[derive(Clone, PartialEq, Debug)]
+pub enum FindingKind {
+    /// Language code or alias contains a `!`
+    InvalidCode(String),
+    /// Keyword is already used by a symbol or keyword in the given section
+    KeywordCollision {
+        /// Repeated keyword
+        keyword: String,
+        /// Section the keyword is first used in
+        first: Section,
+    },
+    /// Manifest key is used more than once
+    DuplicateManifestKey(String),
+    /// Digits are not all the same number of bytes when UTF-8 encoded
+    DigitLengthsDiffer,
+    /// Digit is used more than once or is a symbol
+    DuplicateDigit(char),
+    /// Decimal digit is also used as a number prefix
+    PrefixReused(char),
+    /// Decimal digit or number prefix is a float exponent marker (`e` or `E`)
+    ExponentMarker(char),
+    /// CLI command is used more than once
+    DuplicateCommand(String),
+    /// CLI long flag is used more than once
+    DuplicateLongFlag(String),
+    /// CLI short flag is used more than once
+    DuplicateShortFlag(char),
+}
+
+/// # Keyword sections
+///
+/// Sections keywords are checked for collisions across
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Section {
+    /// Built in symbols such as `+` or `{`
+    Symbols,
+    /// [Control keywords](crate::ControlKwds)
+    Control,
+    /// [Type keywords](crate::TypeKwds)
+    Type,
+    /// [Primitive keywords](crate::PrimitiveKwds)
+    Primitive,
+    /// [Boolean keywords](crate::BoolKwds)
+    Bool,
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Section::Symbols => "symbols",
+            Section::Control => "control keywords",
+            Section::Type => "type keywords",
+            Section::Primitive => "primitive keywords",
+            Section::Bool => "boolean keywords",
+        })
+    }
+}
+
+impl Display for FindingKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FindingKind::InvalidCode(c) => write!(f, "language code `{}` contains a `!`", c),
+            FindingKind::KeywordCollision { keyword, first } => write!(f, "keyword `{0}` collides with `{0}` in {1}", keyword, first),
+            FindingKind::DuplicateManifestKey(k) => write!(f, "manifest key `{}` duplicated", k),
+            FindingKind::DigitLengthsDiffer => write!(f, "digit byte lengths differ"),
+            FindingKind::DuplicateDigit(c) => write!(f, "digit `{}` duplicated or is a symbol", c),
+            FindingKind::PrefixReused(c) => write!(f, "digit `{}` reused as a prefix", c),
+            FindingKind::ExponentMarker(c) => write!(f, "`{}` is used as a float exponent marker", c),
+            FindingKind::DuplicateCommand(c) => write!(f, "CLI command `{}` duplicated", c),
+            FindingKind::DuplicateLongFlag(a) => write!(f, "CLI long flag `{}` duplicated", a),
+            FindingKind::DuplicateShortFlag(a) => write!(f, "CLI short flag `{}` duplicated", a),
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
     }
 }
 
@@ -22,40 +129,56 @@ impl LanguageRaw<'_> {
 ///
 /// Allows a language to check if it's valid
 pub trait Verification {
-    /// Check if a struct is valid for a given language. Returns `true` if it's valid, otherwise
-    /// returns `false`
-    // todo: change return to a better error
-    fn is_invalid(&self) -> bool;
+    /// Check if a struct is valid for a given language. Returns every problem found, so an empty
+    /// list means the struct is valid. `line` is the fckl line the struct starts on
+    fn verify(&self, line: usize) -> Vec<Finding>;
+
+    /// Returns `true` if [`verify`](Self::verify) finds any problems
+    fn is_invalid(&self) -> bool {
+        !self.verify(1).is_empty()
+    }
 }
 
 impl Verification for Keywords<'_> {
-    fn is_invalid(&self) -> bool {
-        if self.digits.is_invalid() { return true }
-        let mut kwds = HashSet::from([
+    fn verify(&self, line: usize) -> Vec<Finding> {
+        let mut out = self.digits.verify(line);
+        let mut kwds = HashMap::from([
             "+", "-", "%", "*", "/", "**", "=", "==", "<", ">", "<=", ">=", "!", "?", ".", ":",
             ";", "!=", "++", "--", "(", ")", "{", "}", "[", "]", "\"", "'", r"\\", r"\*", r"*\",
             "@", r"\\\"
-        ]);
+        ].map(|t| (t, Section::Symbols)));
         macro_rules! simple_lists {
-		    ($kwds:ident, $($l:ident),*$(,)?) => {$(
+		    ($kwds:ident, $(($l:ident, $s:ident, $ln:literal)),*$(,)?) => {$(
 				for i in self.$l.into_iter() {
-					if !$kwds.insert(i) {
-						return true
+					if let Some(first) = $kwds.get(i) {
+						out.push(Finding {
+							line: line + $ln,
+							kind: FindingKind::KeywordCollision { keyword: i.to_string(), first: *first }
+						})
+					} else {
+						$kwds.insert(i, Section::$s);
 					}
 				}
 			)*
 			};
 		}
-        simple_lists!(kwds, keywords, type_kwds, builtins, bool);
-        if self.manifest_keys.into_iter().unique().count() != self.manifest_keys.len() {
-            return true;
+        simple_lists!(kwds, (keywords, Control, 1), (type_kwds, Type, 2), (builtins, Primitive, 3), (bool, Bool, 4));
+        let mut manifest = HashSet::new();
+        for i in self.manifest_keys.into_iter() {
+            if !manifest.insert(i) {
+                out.push(Finding { line: line + 5, kind: FindingKind::DuplicateManifestKey(i.to_string()) })
+            }
         }
-        false
+        out
     }
 }
 
 impl Verification for Digits {
-    fn is_invalid(&self) -> bool {
+    fn verify(&self, line: usize) -> Vec<Finding> {
+        let mut out = Vec::new();
+        macro_rules! finding {
+            ($kind:expr) => { out.push(Finding { line, kind: $kind }) };
+        }
         let mut check = HashSet::from([
             '+', '-', '%', '*', '/', '=', '<', '>', '!', '?',
             '.', ':', ';', '(', ')', '{', '}', '[', ']',
@@ -64,48 +187,60 @@ impl Verification for Digits {
             Digits::Short(t) => ([t.bin_pre, t.hex_pre, t.oct_pre], t.digits.to_vec(), t.u8arrays.map(|(_, l)| 4 - l).to_vec()),
             Digits::Long(t) => ([t.bin_pre, t.hex_pre, t.oct_pre], t.digits.to_vec(), t.u8arrays.map(|(_, l)| 4 - l).to_vec()),
         };
-        // can be unsafe since we know the vector is non-empty
-        let (bl_first, bl_rem) = unsafe { byte_lengths.split_first().unwrap_unchecked() };
-        for i in bl_rem {
-            if bl_first != i { return true }
+        if byte_lengths.iter().any(|t| *t != byte_lengths[0]) {
+            finding!(FindingKind::DigitLengthsDiffer)
         }
-        // check the prefixes are unique and are not in 0..=9
-        for i in 0..10 {
-            if !check.insert(digits[i]) { return true }
+        // check the decimal digits are unique and the prefixes are not in 0..=9
+        for i in &digits[..10] {
+            if !check.insert(*i) { finding!(FindingKind::DuplicateDigit(*i)) }
         }
         for i in pre {
-            if check.contains(&i) { return true }
+            if digits[..10].contains(&i) { finding!(FindingKind::PrefixReused(i)) }
+            else if check.contains(&i) { finding!(FindingKind::DuplicateDigit(i)) }
         }
-        // check the digit characters are all unique
-        for i in 10..16 {
-            if !check.insert(digits[i]) { return true }
+        // float exponents are marked with 'e' or 'E'
+        for i in digits[..10].iter().chain(pre.iter()) {
+            if *i == 'e' || *i == 'E' { finding!(FindingKind::ExponentMarker(*i)) }
         }
-        if let Digits::Long(_) = self {
-            for i in 16..22 {
-                if !check.insert(digits[i]) { return true }
-            }
+        // check the digit characters are all unique
+        for i in &digits[10..] {
+            if !check.insert(*i) { finding!(FindingKind::DuplicateDigit(*i)) }
         }
-        false
+        out
     }
 }
 
 impl Verification for Messages<'_> {
-    fn is_invalid(&self) -> bool {
-        self.cli_keywords.is_invalid()
+    fn verify(&self, line: usize) -> Vec<Finding> {
+        let e = &self.errors;
+        let w = &self.warnings;
+        let lines = e.e00.len() + e.e01.len() + e.e02.len() + e.e03.len() + e.e04.len()
+            + w.w00.len() + w.w01.len() + w.w02.len() + w.w03.len() + w.w04.len();
+        self.cli_keywords.verify(line + lines)
     }
 }
 
 impl Verification for CLIKeywords<'_> {
-    fn is_invalid(&self) -> bool {
-        if self.commands.into_iter().map(|t| t.0).unique().count() != self.commands.len() {
-            return true;
-        }
-        if self.args.into_iter().map(|t| t.0).unique().count() != self.args.len() {
-            return true;
+    fn verify(&self, line: usize) -> Vec<Finding> {
+        let mut out = Vec::new();
+        // description is on the first line then two lines per command and argument
+        let mut commands = HashSet::new();
+        for (n, (cmd, _)) in self.commands.into_iter().enumerate() {
+            if !commands.insert(cmd) {
+                out.push(Finding { line: line + 1 + n * 2, kind: FindingKind::DuplicateCommand(cmd.to_string()) })
+            }
         }
-        if self.args.into_iter().map(|t| t.1).unique().count() != self.args.len() {
-            return true;
+        let args_line = line + 1 + self.commands.len() * 2;
+        let mut long = HashSet::new();
+        let mut short = HashSet::new();
+        for (n, (l, s, _)) in self.args.into_iter().enumerate() {
+            if !long.insert(l) {
+                out.push(Finding { line: args_line + n * 2, kind: FindingKind::DuplicateLongFlag(l.to_string()) })
+            }
+            if !short.insert(s) {
+                out.push(Finding { line: args_line + n * 2, kind: FindingKind::DuplicateShortFlag(s) })
+            }
         }
-        false
+        out
     }
 }
@@ -43,12 +43,12 @@ use syn::{parse::Parser, punctuated::Punctuated, Error, Ident, LitStr, Token, pa
 /// 	pub const LANG: LanguageRaw = /* .. */;
 /// 	pub const MAP: (dyn Table, dyn Table, dyn Table) = /* .. */;
 /// }
-/// pub fn get(l: &str) -> Option<(&LanguageRaw, &(Box<dyn Table>, Box<dyn Table>, Box<dyn Table>))> {
+/// pub fn get<'a>(l: &str, buf: &'a [LanguageTuple<'a>]) -> Result<LanguageTupleRef<'a>, LookupError> {
 /// 	/* ... */
 /// }
 pub fn languages(mods: pmTS) -> pmTS {
     let mut modules = Vec::new();
-    let mut arms = Vec::new();
+    let mut builtins = Vec::new();
 	
 	let mut base = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
 	base = base.parent().unwrap().to_path_buf();
@@ -70,8 +70,10 @@ pub fn languages(mods: pmTS) -> pmTS {
 			Ok(lang) => lang,
 			Err(e) => return pmTS::from(Error::new(module.span(), format!("Unable to parse {}: {}", mod_str, e)).to_compile_error())
 		};
-		if lang.is_invalid() {
-			return pmTS::from(Error::new(module.span(), format!("Language {} failed verification", mod_str)).to_compile_error())
+		let findings = lang.verify();
+		if !findings.is_empty() {
+			let findings = findings.iter().map(|t| format!("\n- {}", t)).collect::<String>();
+			return pmTS::from(Error::new(module.span(), format!("Language {} failed verification:{}", mod_str, findings)).to_compile_error())
 		}
         let doc1 = format!(
             "Autogenerated language module for {} ({})",
@@ -142,9 +144,8 @@ pub fn languages(mods: pmTS) -> pmTS {
                 pub const MAP: (#ty1, #ty2, #ty3) = (#map1, #map2, #map3);
             }
         });
-        let mod_str = LitStr::new(&*mod_str, Span::mixed_site());
-        arms.push(quote! {
-            #mod_str => return Some((&#module::LANG, (&#module::MAP.0, &#module::MAP.1, &#module::MAP.2))),
+        builtins.push(quote! {
+            (&#module::LANG, (&#module::MAP.0, &#module::MAP.1, &#module::MAP.2))
         })
     }
 	#[cfg(feature = "table-page")]
@@ -154,6 +155,7 @@ pub fn languages(mods: pmTS) -> pmTS {
 			return pmTS::from(Error::new(Span::mixed_site(), format!("Table HTML page gen failed: {}", e)).to_compile_error())
 		}
 	}
+    let count = builtins.len();
     pmTS::from(quote! {
         #(#modules)*
 
@@ -180,33 +182,39 @@ pub fn languages(mods: pmTS) -> pmTS {
 		/// and maps are passed as references. this is the type returned when getting a language
 		pub type LanguageTupleRef<'a> = (&'a LanguageRaw<'a>, (&'a dyn Table<u16>, &'a dyn Table<u8>, &'a dyn Table<u8>));
 
-        /// Get language and map from a language code
+		/// Built-in languages and maps
+		fn builtins<'a>() -> [LanguageTupleRef<'a>; #count] {
+			[#(#builtins),*]
+		}
+		
+        /// Get language and map from a language code, full name, or alias
 		///
-		/// Try to get a built-in language from a language code. This will return a
+		/// Try to get a built-in or custom language. This will return a
 		/// [`LanguageRaw`](lang_inner::LanguageRaw) and a language transition map to be used with
-		/// [`tokenize`](crate::lexer::tokenize).
+		/// [`tokenize`](crate::lexer::tokenize). If more than one language matches, a
+		/// [`LookupError::Ambiguous`] is returned.
 		///
-		/// **Note that the language code is made lowercase so `EN` is equivalent to `en`.**
-        pub fn get<'a>(l: &str, buf: &'a [LanguageTuple<'a>]) -> Option<LanguageTupleRef<'a>> {
-			let l = &*l.to_lowercase();
-            match l {
-                #(#arms)*
-                _ => {}
-            }
-			if let Some((v, (m1, m2, m3))) = buf.iter().find(|(t, _)| t.name.0 == l) {
-				return Some((v, (m1, m2, m3)))
+		/// **Note that matching ignores case so `EN` is equivalent to `en`.**
+        pub fn get<'a>(l: &str, buf: &'a [LanguageTuple<'a>]) -> Result<LanguageTupleRef<'a>, LookupError> {
+			let mut found = builtins().into_iter()
+				.filter(|(t, _)| t.matches(l))
+				.collect::<Vec<_>>();
+			found.extend(buf.iter()
+				.filter(|(t, _)| t.matches(l))
+				.map(|(v, (m1, m2, m3))| -> LanguageTupleRef<'a> { (v, (m1, m2, m3)) }));
+			match found.len() {
+				0 => Err(LookupError::NotFound(l.to_string())),
+				1 => Ok(found.remove(0)),
+				_ => Err(LookupError::Ambiguous {
+					name: l.to_string(),
+					codes: found.iter().map(|(t, _)| t.name.1.to_string()).collect(),
+				}),
 			}
-			None
         }
 		
 		/// Get language with no language buffer. See [`get`]
-		pub fn get_no_buffer<'a>(l: &str) -> Option<LanguageTupleRef<'a>> {
-			let l = &*l.to_lowercase();
-            match l {
-                #(#arms)*
-                _ => {}
-            }
-			None
+		pub fn get_no_buffer<'a>(l: &str) -> Result<LanguageTupleRef<'a>, LookupError> {
+			get(l, &[])
 		}
     })
 }
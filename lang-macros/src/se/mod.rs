@@ -19,12 +19,14 @@ impl Serialize for LanguageRaw<'_> {
     fn serialize(self) -> TokenStream {
         let (n1, n2) = self.name;
         let name = quote!{name: (#n1, #n2)};
+        let aliases = self.aliases;
+        let aliases = quote!{aliases: #aliases};
         let left_right = self.left_right;
         let left_right = quote!{left_right: #left_right};
         let kwds = self.keywords.serialize();
         let messages = self.messages.serialize();
         quote!{
-            LanguageRaw { #name, #left_right, keywords: #kwds, messages: #messages }
+            LanguageRaw { #name, #aliases, #left_right, keywords: #kwds, messages: #messages }
         }
     }
 }
@@ -0,0 +1,120 @@
+//! # Confusable identifiers
+//!
+//! Files can switch between languages, so two identifiers that look the same can be made of
+//! characters from different scripts, such as Latin `a` and Cyrillic `а`. These are different
+//! identifiers to the lexer but not to someone reading the code. This module finds identifiers
+//! that are confusable with another identifier or a keyword using the confusables skeleton from
+//! [UTS #39](https://www.unicode.org/reports/tr39/#Confusable_Detection)
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use lang_inner::LanguageRaw;
+use unicode_security::skeleton;
+use crate::tok::{Position, Token, TokType};
+use crate::{builtins, LanguageTuple};
+
+/// # Confusable identifier warning
+///
+/// An identifier that is different to, but has the same skeleton as, an earlier identifier or a
+/// keyword from a loaded language (`w0001`)
+#[derive(Clone, PartialEq)]
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+pub struct Confusable {
+	/// Start of the identifier
+	pub ps: Position,
+	/// End of the identifier
+	pub pe: Position,
+	/// Identifier
+	pub ident: String,
+	/// Code of the language the identifier was found in
+	pub lang: String,
+	/// What the identifier is confusable with
+	pub with: ConfusableWith,
+}
+
+/// # Confusable with
+///
+/// What a [`Confusable`] identifier is confusable with
+#[derive(Clone, PartialEq)]
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+pub enum ConfusableWith {
+	/// An earlier identifier in the token stream
+	Identifier {
+		/// Start of the earlier identifier
+		ps: Position,
+		/// Earlier identifier
+		ident: String,
+	},
+	/// A keyword from a loaded language
+	Keyword {
+		/// Keyword
+		kwd: String,
+		/// Code of the language the keyword is from
+		lang: String,
+	},
+}
+
+impl Confusable {
+	/// Warning code. This is always `(0, 0)` (`w0001`)
+	pub fn code(&self) -> (u8, u8) {
+		(0, 0)
+	}
+
+	/// Get the localised warning message from a language
+	pub fn message<'a>(&self, l: &'a LanguageRaw<'_>) -> &'a str {
+		&l.messages.warnings[self.code()]
+	}
+}
+
+impl Display for Confusable {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let (i1, i2) = self.code();
+		write!(f, "w{:02}{:02} at {}:{} ({}): identifier {:?} is confusable with ", i1, i2 + 1, self.ps.ln, self.ps.col, self.lang, self.ident)?;
+		match &self.with {
+			ConfusableWith::Identifier { ps, ident } => write!(f, "identifier {:?} at {}:{}", ident, ps.ln, ps.col),
+			ConfusableWith::Keyword { kwd, lang } => write!(f, "keyword {:?} ({})", kwd, lang),
+		}
+	}
+}
+
+/// # Find confusable identifiers
+///
+/// Check each identifier in a token stream against the keywords of the built-in languages and the
+/// languages in `buf`, then against every different identifier before it. Each identifier token is
+/// reported at most once, with keywords taking priority over identifiers
+pub fn confusables(toks: &[Token], buf: &[LanguageTuple<'_>]) -> Vec<Confusable> {
+	let languages = builtins().into_iter().map(|(l, _)| l).chain(buf.iter().map(|(l, _)| l));
+	let mut keywords = HashMap::new();
+	for l in languages {
+		let k = &l.keywords;
+		for kwd in k.keywords.into_iter().chain(k.type_kwds).chain(k.builtins).chain(k.bool) {
+			keywords.entry(skeleton(kwd).collect::<String>()).or_insert_with(Vec::new).push((kwd, l.name.1));
+		}
+	}
+
+	let mut idents: HashMap<String, Vec<(&str, Position)>> = HashMap::new();
+	let mut out = Vec::new();
+	for tok in toks {
+		let TokType::Identifier(lang, bytes) = &tok.tt else { continue };
+		let Ok(ident) = std::str::from_utf8(bytes) else { continue };
+		let skel = skeleton(ident).collect::<String>();
+		// an identifier matching a keyword from another language is the same word, not a lookalike
+		let kwds = keywords.get(&skel).map(|t| t.as_slice()).unwrap_or_default();
+		let with = match kwds.first() {
+			Some((kwd, kwd_lang)) if kwds.iter().all(|(k, _)| *k != ident) => Some(ConfusableWith::Keyword {
+				kwd: kwd.to_string(), lang: kwd_lang.to_string()
+			}),
+			_ => idents.get(&skel)
+				.and_then(|t| t.iter().find(|(i, _)| *i != ident))
+				.map(|(i, ps)| ConfusableWith::Identifier { ps: *ps, ident: i.to_string() }),
+		};
+		if let Some(with) = with {
+			out.push(Confusable { ps: tok.ps, pe: tok.pe, ident: ident.to_string(), lang: lang.clone(), with })
+		}
+		let seen = idents.entry(skel).or_default();
+		if seen.iter().all(|(i, _)| *i != ident) {
+			seen.push((ident, tok.ps))
+		}
+	}
+	out
+}
@@ -0,0 +1,226 @@
+//! # Lexer errors
+//!
+//! Contains the [`LexError`] type returned when lexing fails. Each error maps to a language error
+//! message so it can be shown in the language being lexed
+
+use std::fmt::{Display, Formatter};
+use lang_inner::LanguageRaw;
+use crate::tok::Position;
+
+/// # Lexing error
+///
+/// Each variant holds the [`Position`] the error starts at and the code of the language active
+/// at that position. Each variant corresponds to an error code in
+/// [`Errors`](lang_inner::Errors), see [`code`](Self::code)
+#[derive(Clone, PartialEq)]
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+pub enum LexError {
+	/// Bytes that do not match any token (`e0001`)
+	UnexpectedBytes {
+		/// Start of the unmatched bytes
+		pos: Position,
+		/// Unmatched bytes
+		bytes: Vec<u8>,
+		/// Active language code
+		lang: String,
+	},
+	/// Unknown language code in a `!!` language switch (`e0002`)
+	UnknownLanguage {
+		/// Position of the `!!`
+		pos: Position,
+		/// Given language code
+		code: Vec<u8>,
+		/// Active language code
+		lang: String,
+	},
+	/// Closing curly brace `}` with no matching `{` (`e0003`)
+	UnmatchedBrace {
+		/// Position of the `}`
+		pos: Position,
+		/// Active language code
+		lang: String,
+	},
+	/// String literal with no closing `"` (`e0004`)
+	UnterminatedString {
+		/// Position of the opening `"`
+		pos: Position,
+		/// Active language code
+		lang: String,
+	},
+	/// Invalid character literal or escaped character (`e0005`)
+	InvalidChar {
+		/// Position of the character
+		pos: Position,
+		/// Bytes read for the character
+		bytes: Vec<u8>,
+		/// Active language code
+		lang: String,
+	},
+	/// Block comment with no closing `*\` (`e0006`). Block comments nest, so each `\*` inside a
+	/// block comment needs its own `*\`
+	UnterminatedComment {
+		/// Position of the outermost opening `\*`
+		pos: Position,
+		/// Active language code
+		lang: String,
+	},
+	/// Backslash not followed by a `\` or `*` to start a comment (`e0007`)
+	InvalidComment {
+		/// Position of the backslash
+		pos: Position,
+		/// Byte following the backslash
+		byte: Option<u8>,
+		/// Active language code
+		lang: String,
+	},
+	/// Unknown or invalid escape sequence in a string or character literal (`e0008`)
+	InvalidEscape {
+		/// Position of the backslash
+		pos: Position,
+		/// Bytes of the escape sequence, including the backslash
+		bytes: Vec<u8>,
+		/// Active language code
+		lang: String,
+	},
+	/// Language name or alias in a `!!` language switch that matches more than one language
+	/// (`e0009`)
+	AmbiguousLanguage {
+		/// Position of the `!!`
+		pos: Position,
+		/// Given language name or alias
+		code: Vec<u8>,
+		/// Codes of the matching languages
+		codes: Vec<String>,
+		/// Active language code
+		lang: String,
+	},
+	/// Input could not be read to the end (`e0010`)
+	ReadError {
+		/// Position reading stopped at
+		pos: Position,
+		/// I/O error message
+		error: String,
+		/// Active language code
+		lang: String,
+	},
+	/// String interpolation `{}` with nothing inside (`e0011`)
+	EmptyInterpolation {
+		/// Position of the `{`
+		pos: Position,
+		/// Active language code
+		lang: String,
+	},
+}
+
+impl LexError {
+	/// Error code as an index into [`Errors`](lang_inner::Errors)
+	///
+	/// | Variant | Code |
+	/// |---|---|
+	/// | [`UnexpectedBytes`](Self::UnexpectedBytes) | `e0001` |
+	/// | [`UnknownLanguage`](Self::UnknownLanguage) | `e0002` |
+	/// | [`UnmatchedBrace`](Self::UnmatchedBrace) | `e0003` |
+	/// | [`UnterminatedString`](Self::UnterminatedString) | `e0004` |
+	/// | [`InvalidChar`](Self::InvalidChar) | `e0005` |
+	/// | [`UnterminatedComment`](Self::UnterminatedComment) | `e0006` |
+	/// | [`InvalidComment`](Self::InvalidComment) | `e0007` |
+	/// | [`InvalidEscape`](Self::InvalidEscape) | `e0008` |
+	/// | [`AmbiguousLanguage`](Self::AmbiguousLanguage) | `e0009` |
+	/// | [`ReadError`](Self::ReadError) | `e0010` |
+	/// | [`EmptyInterpolation`](Self::EmptyInterpolation) | `e0011` |
+	pub fn code(&self) -> (u8, u8) {
+		match self {
+			Self::UnexpectedBytes { .. } => (0, 0),
+			Self::UnknownLanguage { .. } => (0, 1),
+			Self::UnmatchedBrace { .. } => (0, 2),
+			Self::UnterminatedString { .. } => (0, 3),
+			Self::InvalidChar { .. } => (0, 4),
+			Self::UnterminatedComment { .. } => (0, 5),
+			Self::InvalidComment { .. } => (0, 6),
+			Self::InvalidEscape { .. } => (0, 7),
+			Self::AmbiguousLanguage { .. } => (0, 8),
+			Self::ReadError { .. } => (0, 9),
+			Self::EmptyInterpolation { .. } => (0, 10),
+		}
+	}
+
+	/// Position the error starts at
+	pub fn pos(&self) -> Position {
+		match self {
+			Self::UnexpectedBytes { pos, .. }
+			| Self::UnknownLanguage { pos, .. }
+			| Self::UnmatchedBrace { pos, .. }
+			| Self::UnterminatedString { pos, .. }
+			| Self::InvalidChar { pos, .. }
+			| Self::UnterminatedComment { pos, .. }
+			| Self::InvalidComment { pos, .. }
+			| Self::InvalidEscape { pos, .. }
+			| Self::AmbiguousLanguage { pos, .. }
+			| Self::ReadError { pos, .. }
+			| Self::EmptyInterpolation { pos, .. } => *pos,
+		}
+	}
+
+	/// Mutable reference to the position the error starts at
+	pub(crate) fn pos_mut(&mut self) -> &mut Position {
+		match self {
+			Self::UnexpectedBytes { pos, .. }
+			| Self::UnknownLanguage { pos, .. }
+			| Self::UnmatchedBrace { pos, .. }
+			| Self::UnterminatedString { pos, .. }
+			| Self::InvalidChar { pos, .. }
+			| Self::UnterminatedComment { pos, .. }
+			| Self::InvalidComment { pos, .. }
+			| Self::InvalidEscape { pos, .. }
+			| Self::AmbiguousLanguage { pos, .. }
+			| Self::ReadError { pos, .. }
+			| Self::EmptyInterpolation { pos, .. } => pos,
+		}
+	}
+
+	/// Code of the language active where the error was found
+	pub fn lang(&self) -> &str {
+		match self {
+			Self::UnexpectedBytes { lang, .. }
+			| Self::UnknownLanguage { lang, .. }
+			| Self::UnmatchedBrace { lang, .. }
+			| Self::UnterminatedString { lang, .. }
+			| Self::InvalidChar { lang, .. }
+			| Self::UnterminatedComment { lang, .. }
+			| Self::InvalidComment { lang, .. }
+			| Self::InvalidEscape { lang, .. }
+			| Self::AmbiguousLanguage { lang, .. }
+			| Self::ReadError { lang, .. }
+			| Self::EmptyInterpolation { lang, .. } => lang,
+		}
+	}
+
+	/// Get the localised error message from a language
+	pub fn message<'a>(&self, l: &'a LanguageRaw<'_>) -> &'a str {
+		&l.messages.errors[self.code()]
+	}
+}
+
+impl Display for LexError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let (i1, i2) = self.code();
+		let pos = self.pos();
+		write!(f, "e{:02}{:02} at {}:{} ({}): ", i1, i2 + 1, pos.ln, pos.col, self.lang())?;
+		match self {
+			Self::UnexpectedBytes { bytes, .. } => write!(f, "unexpected bytes {:?}", String::from_utf8_lossy(bytes)),
+			Self::UnknownLanguage { code, .. } => write!(f, "unknown language {:?}", String::from_utf8_lossy(code)),
+			Self::UnmatchedBrace { .. } => write!(f, "unmatched closing brace"),
+			Self::UnterminatedString { .. } => write!(f, "unterminated string"),
+			Self::InvalidChar { bytes, .. } => write!(f, "invalid character {:?}", bytes),
+			Self::UnterminatedComment { .. } => write!(f, "unterminated block comment"),
+			Self::InvalidComment { byte, .. } => match byte {
+				Some(b) => write!(f, "expected comment, found {:?}", *b as char),
+				None => write!(f, "expected comment, found nothing"),
+			},
+			Self::InvalidEscape { bytes, .. } => write!(f, "invalid escape sequence {:?}", String::from_utf8_lossy(bytes)),
+			Self::AmbiguousLanguage { code, codes, .. } => write!(f, "{:?} is ambiguous. Could be any of {}", String::from_utf8_lossy(code), codes.join(", ")),
+			Self::ReadError { error, .. } => write!(f, "failed to read input: {}", error),
+			Self::EmptyInterpolation { .. } => write!(f, "empty string interpolation"),
+		}
+	}
+}
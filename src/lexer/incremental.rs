@@ -0,0 +1,237 @@
+//! # Incremental lexing
+//!
+//! Re-lexes only the part of an input affected by an edit. While lexing, a [`Checkpoint`] is
+//! recorded at every token boundary. After an edit, lexing restarts from the last checkpoint
+//! before the edit and stops once the lexer reaches a checkpoint after the edit with the same
+//! language and scopes. The tokens after that point are reused and moved to their new positions
+
+use std::ops::Range;
+use lang_inner::{LanguageRaw, Table};
+use crate::tok::{Position, RunningPosition, Token};
+use crate::{LanguageTuple, LanguageTupleRef};
+use super::{LexError, Lexer, NFABranch};
+
+/// # Lexer checkpoint
+///
+/// Lexer state at a token boundary. Lexing can be restarted from a checkpoint without lexing the
+/// input before it
+#[derive(Clone)]
+pub struct Checkpoint<'a> {
+	/// Running in-text position
+	pos: RunningPosition,
+	/// Active language and tables
+	lang: LanguageTupleRef<'a>,
+	/// Language active outside each open curly brace
+	scopes: Vec<LanguageTupleRef<'a>>,
+	/// Number of open curly braces outside each open string interpolation, the start of its
+	/// string, and the position of its `{` while it is empty
+	interpolations: Vec<(usize, Position, Option<Position>)>,
+	/// Number of tokens before the checkpoint
+	tokens: usize,
+	/// Number of errors before the checkpoint
+	errors: usize,
+}
+
+impl<'a> Checkpoint<'a> {
+	/// Byte offset into the input
+	pub fn offset(&self) -> usize {
+		self.pos.finish().offset
+	}
+
+	/// Position in the input
+	pub fn position(&self) -> Position {
+		self.pos.finish()
+	}
+
+	/// Active language
+	pub fn language(&self) -> &'a LanguageRaw<'a> {
+		self.lang.0
+	}
+
+	/// Number of open curly braces
+	pub fn depth(&self) -> usize {
+		self.scopes.len()
+	}
+
+	/// Check if the lexer state is the same as another checkpoint, ignoring the position
+	fn same_state(&self, other: &Self) -> bool {
+		self.pos.previous == other.pos.previous
+			&& std::ptr::eq(self.lang.0, other.lang.0)
+			&& self.scopes.len() == other.scopes.len()
+			&& self.scopes.iter().zip(&other.scopes).all(|(a, b)| std::ptr::eq(a.0, b.0))
+			&& self.interpolations == other.interpolations
+	}
+}
+
+/// # Incremental lexer
+///
+/// Holds an input with its tokens and errors, and updates them after each
+/// [edit](Self::edit) by re-lexing only the affected region. Errors are recovered from as with
+/// [`tokenize_recover`](super::tokenize_recover)
+pub struct IncrementalLexer<'a> {
+	/// Input text
+	text: Vec<u8>,
+	/// Tokens lexed from the text
+	tokens: Vec<Token>,
+	/// Errors found in the text
+	errors: Vec<LexError>,
+	/// Checkpoints at token boundaries in increasing offset order. The first checkpoint is
+	/// always the start of the text
+	checkpoints: Vec<Checkpoint<'a>>,
+	/// Custom language buffer for `!!` language switches
+	buf: &'a [LanguageTuple<'a>],
+}
+
+impl<'a> IncrementalLexer<'a> {
+	/// Lex a new input
+	pub fn new(
+		text: Vec<u8>,
+		l: &'a LanguageRaw<'a>,
+		buf: &'a [LanguageTuple<'a>],
+		tables: (&'a dyn Table<u16>, &'a dyn Table<u8>, &'a dyn Table<u8>),
+	) -> Self {
+		let start = Checkpoint {
+			pos: RunningPosition::new(),
+			lang: (l, tables),
+			scopes: Vec::new(),
+			interpolations: Vec::new(),
+			tokens: 0,
+			errors: 0,
+		};
+		let (tokens, errors, mut checkpoints, _) = lex_from(&text, &start, buf, |_| None);
+		checkpoints.insert(0, start);
+		Self { text, tokens, errors, checkpoints, buf }
+	}
+
+	/// Input text
+	pub fn text(&self) -> &[u8] {
+		&self.text
+	}
+
+	/// Tokens lexed from the text
+	pub fn tokens(&self) -> &[Token] {
+		&self.tokens
+	}
+
+	/// Errors found in the text
+	pub fn errors(&self) -> &[LexError] {
+		&self.errors
+	}
+
+	/// Checkpoints recorded at token boundaries
+	pub fn checkpoints(&self) -> &[Checkpoint<'a>] {
+		&self.checkpoints
+	}
+
+	/// Replace a byte range of the text and re-lex the affected region
+	///
+	/// Returns the range of [`tokens`](Self::tokens) that were re-lexed. Tokens outside this range
+	/// are kept from before the edit, with their positions moved to account for the edit.
+	///
+	/// # Panics
+	///
+	/// Panics if the range is out of bounds, as with [`Vec::splice`]
+	pub fn edit(&mut self, range: Range<usize>, replacement: &[u8]) -> Range<usize> {
+		let Range { start, end: old_end } = range;
+		let new_end = start + replacement.len();
+		self.text.splice(range, replacement.iter().copied());
+		// the step from the checkpoint before the edit may have peeked the first edited byte
+		let restart = self.checkpoints.iter().rposition(|c| c.offset() < start).unwrap_or(0);
+		let old = &self.checkpoints;
+		let (tokens, errors, mut checkpoints, synced) = lex_from(&self.text, &old[restart], self.buf, |c| {
+			if c.offset() < new_end { return None }
+			let offset = c.offset() - new_end + old_end;
+			let i = old.binary_search_by_key(&offset, Checkpoint::offset).ok()?;
+			(i > restart && old[i].same_state(c)).then_some(i)
+		});
+		let Checkpoint { tokens: tok_start, errors: err_start, .. } = self.checkpoints[restart];
+		let relexed = tok_start..tok_start + tokens.len();
+		let old_tokens = std::mem::take(&mut self.tokens);
+		let old_errors = std::mem::take(&mut self.errors);
+		let old_checkpoints = std::mem::take(&mut self.checkpoints);
+		self.tokens = old_tokens[..tok_start].to_vec();
+		self.tokens.extend(tokens);
+		self.errors = old_errors[..err_start].to_vec();
+		self.errors.extend(errors);
+		self.checkpoints = old_checkpoints[..=restart].to_vec();
+		if let Some(i) = synced {
+			// the last new checkpoint replaces the old checkpoint `i`
+			let to = checkpoints.pop().map_or(Position::default(), |c| c.pos.finish());
+			self.checkpoints.extend(checkpoints);
+			let Checkpoint { pos, tokens: sync_tokens, errors: sync_errors, .. } = old_checkpoints[i];
+			let from = pos.finish();
+			let (tok_shift, err_shift) = (self.tokens.len(), self.errors.len());
+			for mut t in old_tokens.into_iter().skip(sync_tokens) {
+				t.ps.shift(from, to);
+				t.pe.shift(from, to);
+				self.tokens.push(t)
+			}
+			for mut e in old_errors.into_iter().skip(sync_errors) {
+				// unterminated strings found at the end of the text point back to their start
+				if e.pos().offset >= from.offset { e.pos_mut().shift(from, to) }
+				self.errors.push(e)
+			}
+			for mut c in old_checkpoints.into_iter().skip(i) {
+				c.pos.shift(from, to);
+				// strings started before the sync point are the same in the new checkpoints
+				for (_, start, brace) in c.interpolations.iter_mut() {
+					for p in std::iter::once(start).chain(brace) {
+						if p.offset >= from.offset { p.shift(from, to) }
+					}
+				}
+				c.tokens = c.tokens - sync_tokens + tok_shift;
+				c.errors = c.errors - sync_errors + err_shift;
+				self.checkpoints.push(c)
+			}
+		} else {
+			self.checkpoints.extend(checkpoints)
+		}
+		relexed
+	}
+}
+
+/// Lex `text` from a checkpoint to the end of the text, or until `sync` returns the index of an
+/// old checkpoint with the same state as a new checkpoint
+///
+/// Returns the new tokens, errors, and checkpoints, and the index returned by `sync` if lexing
+/// stopped early. The starting checkpoint is not included
+fn lex_from<'a>(
+	text: &[u8],
+	start: &Checkpoint<'a>,
+	buf: &'a [LanguageTuple<'a>],
+	mut sync: impl FnMut(&Checkpoint<'a>) -> Option<usize>,
+) -> (Vec<Token>, Vec<LexError>, Vec<Checkpoint<'a>>, Option<usize>) {
+	let mut lexer = Lexer::new(text[start.offset()..].iter().copied(), start.lang.0, buf, start.lang.1).recover(true);
+	lexer.pos = start.pos;
+	lexer.tree = NFABranch::new(Vec::new(), start.pos);
+	lexer.language_scopes = start.scopes.clone();
+	lexer.interpolations = start.interpolations.clone();
+	let mut tokens = Vec::new();
+	let mut errors = Vec::new();
+	let mut checkpoints = Vec::new();
+	while !lexer.done {
+		lexer.step();
+		for t in lexer.queue.drain(..) {
+			match t {
+				Ok(t) => tokens.push(t),
+				Err(e) => errors.push(e),
+			}
+		}
+		// the lexer can stop mid-token at the end of the text
+		if lexer.done || lexer.tree.row != 0 || !lexer.tree.matched.is_empty() { continue }
+		let c = Checkpoint {
+			pos: lexer.pos,
+			lang: lexer.current_lang,
+			scopes: lexer.language_scopes.clone(),
+			interpolations: lexer.interpolations.clone(),
+			tokens: start.tokens + tokens.len(),
+			errors: start.errors + errors.len(),
+		};
+		let synced = sync(&c);
+		checkpoints.push(c);
+		if synced.is_some() {
+			return (tokens, errors, checkpoints, synced)
+		}
+	}
+	(tokens, errors, checkpoints, None)
+}
@@ -0,0 +1,180 @@
+//! # Lossless lexing
+//!
+//! Lexes an input keeping every byte. Whitespace, `!!` language switches, and comments are
+//! attached to the surrounding tokens as [`Trivia`], and each token keeps the bytes it was lexed
+//! from. Concatenating the [full text](LosslessToken::full_text) of each token gives back the
+//! input byte-for-byte.
+//!
+//! Trivia after a token up to the next token is trailing trivia, unless the token is a
+//! [`NewLine`](TokType::NewLine). Trivia after a newline, or at the start of the input, is leading
+//! trivia of the next token. The last item is always an end of input item with no token
+
+use std::collections::VecDeque;
+use crate::tok::{Position, Token, TokType};
+use super::{LexError, Lexer};
+
+/// # Trivia
+///
+/// Bytes between tokens that don't affect parsing
+#[derive(Clone, PartialEq)]
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub struct Trivia {
+	/// Trivia type
+	pub kind: TriviaKind,
+	/// Bytes of the trivia
+	pub text: Vec<u8>,
+}
+
+/// # Trivia type
+#[derive(Clone, PartialEq)]
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub enum TriviaKind {
+	/// Whitespace, and any other bytes between tokens
+	Whitespace,
+	/// `!!` language switch with the given language code
+	LanguageSwitch(String),
+	/// Comment in the given language
+	Comment(String),
+}
+
+/// # Lossless token
+///
+/// Token with its leading and trailing trivia, and the bytes it was lexed from
+#[derive(Clone, PartialEq)]
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub struct LosslessToken {
+	/// Trivia before the token
+	pub leading: Vec<Trivia>,
+	/// Token. This is `None` for the end of input
+	pub token: Option<Token>,
+	/// Bytes the token was lexed from
+	pub text: Vec<u8>,
+	/// Trivia after the token
+	pub trailing: Vec<Trivia>,
+}
+
+impl LosslessToken {
+	/// Leading trivia, token, and trailing trivia bytes
+	pub fn full_text(&self) -> Vec<u8> {
+		let mut out = Vec::new();
+		for t in &self.leading { out.extend(&t.text) }
+		out.extend(&self.text);
+		for t in &self.trailing { out.extend(&t.text) }
+		out
+	}
+
+	/// Check if the token is a newline
+	fn is_newline(&self) -> bool {
+		matches!(self.token, Some(Token { tt: TokType::NewLine(_), .. }))
+	}
+}
+
+/// # Lossless lexer
+///
+/// Made with [`Lexer::lossless`]. See the [module docs](self)
+pub struct Lossless<'a, B: Iterator<Item = u8>> {
+	lexer: Lexer<'a, B>,
+	/// Offset of the first byte in the lexer record
+	cursor: usize,
+	/// Last token, waiting for its trailing trivia
+	previous: Option<LosslessToken>,
+	/// Trivia since the last token
+	pending: Vec<Trivia>,
+	/// Tokens and errors ready to be returned
+	queue: VecDeque<Result<LosslessToken, LexError>>,
+	/// End of input has been reached
+	done: bool,
+}
+
+impl<'a, B: Iterator<Item = u8>> Lexer<'a, B> {
+	/// Keep every byte of the input. See [`Lossless`]
+	pub fn lossless(mut self) -> Lossless<'a, B> {
+		self.bytes.record = Some(VecDeque::new());
+		Lossless {
+			lexer: self,
+			cursor: 0,
+			previous: None,
+			pending: Vec::new(),
+			queue: VecDeque::new(),
+			done: false,
+		}
+	}
+}
+
+impl<B: Iterator<Item = u8>> Lossless<'_, B> {
+	/// Take recorded bytes up to a position
+	fn take_to(&mut self, p: Position) -> Vec<u8> {
+		let record = self.lexer.bytes.record.get_or_insert_with(VecDeque::new);
+		let len = p.offset.saturating_sub(self.cursor).min(record.len());
+		self.cursor += len;
+		record.drain(..len).collect()
+	}
+
+	/// Split bytes between tokens into trivia and add them to the pending trivia. `!!` language
+	/// switches are only found here when [`Lexer::language_switches`] is off
+	fn gap(&mut self, mut bytes: &[u8]) {
+		use crate::tok::consts::*;
+		while !bytes.is_empty() {
+			let (kind, len) = if bytes.starts_with(&[EXCLAMATION_MARK; 2]) {
+				let end = bytes[2..].iter().position(|t| *t == NEWLINE || *t == SEMICOLON);
+				let code = &bytes[2..end.map_or(bytes.len(), |t| t + 2)];
+				let kind = TriviaKind::LanguageSwitch(String::from_utf8_lossy(code).to_string());
+				(kind, end.map_or(bytes.len(), |t| t + 3))
+			} else {
+				let len = bytes.windows(2).position(|t| t == [EXCLAMATION_MARK; 2]).unwrap_or(bytes.len());
+				(TriviaKind::Whitespace, len)
+			};
+			self.pending.push(Trivia { kind, text: bytes[..len].to_vec() });
+			bytes = &bytes[len..]
+		}
+	}
+
+	/// Attach the pending trivia to the previous token or to `next`, then queue the previous token
+	fn push(&mut self, mut next: LosslessToken) {
+		let pending = std::mem::take(&mut self.pending);
+		match self.previous.take() {
+			Some(mut previous) => {
+				if previous.is_newline() { next.leading = pending } else { previous.trailing = pending }
+				self.queue.push_back(Ok(previous))
+			}
+			None => next.leading = pending,
+		}
+		self.previous = Some(next)
+	}
+}
+
+impl<B: Iterator<Item = u8>> Iterator for Lossless<'_, B> {
+	type Item = Result<LosslessToken, LexError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(t) = self.queue.pop_front() { return Some(t) }
+			if self.done { return None }
+			match self.lexer.next() {
+				Some(Ok(token)) => {
+					let gap = self.take_to(token.ps);
+					self.gap(&gap);
+					let text = self.take_to(token.pe);
+					let kind = match &token.tt {
+						TokType::Comment(lang, _) => Some(TriviaKind::Comment(lang.clone())),
+						TokType::LanguageSwitch(lang) => Some(TriviaKind::LanguageSwitch(lang.clone())),
+						_ => None
+					};
+					if let Some(kind) = kind {
+						self.pending.push(Trivia { kind, text });
+						continue
+					}
+					self.push(LosslessToken { leading: Vec::new(), token: Some(token), text, trailing: Vec::new() })
+				}
+				Some(Err(e)) => self.queue.push_back(Err(e)),
+				None => {
+					self.done = true;
+					let rest = self.lexer.bytes.record.take().unwrap_or_default();
+					self.gap(&Vec::from(rest));
+					self.push(LosslessToken { leading: Vec::new(), token: None, text: Vec::new(), trailing: Vec::new() });
+					self.queue.extend(self.previous.take().map(Ok))
+				}
+			}
+		}
+	}
+}
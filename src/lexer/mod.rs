@@ -0,0 +1,1135 @@
+mod err;
+mod incremental;
+mod lossless;
+
+use crate::{tok::{Position, RunningPosition, Token, PreTokType, PreToken, TokType}, LanguageTuple, LanguageTupleRef, LookupError};
+use lang_inner::{LanguageRaw, Table};
+#[cfg(debug_assertions)]
+use std::fmt::{Debug, Formatter};
+use std::collections::VecDeque;
+use std::io::{BufReader, Read};
+pub use err::LexError;
+pub use incremental::{Checkpoint, IncrementalLexer};
+pub use lossless::{Lossless, LosslessToken, Trivia, TriviaKind};
+
+/// # Tokenize an input
+///
+/// Turn an input into a token stream or return an error in parsing the input
+pub fn tokenize<'a, B: Iterator<Item = u8>>(
+	bytes: B,
+	l: &LanguageRaw<'a>,
+	buf: &[LanguageTuple<'a>],
+	tables: (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>),
+) -> Result<Vec<Token>, LexError> {
+	Lexer::new(bytes, l, buf, tables).collect()
+}
+
+/// # Tokenize an input with error recovery
+///
+/// Turn an input into a best-effort token stream and every error found in the input. When an
+/// error is found, the bytes up to the next whitespace or curly brace are skipped and returned as
+/// a [`TokType::Error`] token, and lexing continues from there
+pub fn tokenize_recover<'a, B: Iterator<Item = u8>>(
+	bytes: B,
+	l: &LanguageRaw<'a>,
+	buf: &[LanguageTuple<'a>],
+	tables: (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>),
+) -> (Vec<Token>, Vec<LexError>) {
+	let mut toks = Vec::new();
+	let mut errors = Vec::new();
+	for t in Lexer::new(bytes, l, buf, tables).recover(true) {
+		match t {
+			Ok(t) => toks.push(t),
+			Err(e) => errors.push(e),
+		}
+	}
+	(toks, errors)
+}
+
+/// # Tokenize an input keeping every byte
+///
+/// Turn an input into a lossless token stream or return an error in parsing the input. See
+/// [`Lossless`]
+pub fn tokenize_lossless<'a, B: Iterator<Item = u8>>(
+	bytes: B,
+	l: &LanguageRaw<'a>,
+	buf: &[LanguageTuple<'a>],
+	tables: (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>),
+) -> Result<Vec<LosslessToken>, LexError> {
+	Lexer::new(bytes, l, buf, tables).lossless().collect()
+}
+
+/// # Streaming lexer
+///
+/// Lexes an input one token at a time. Bytes are only read from the input when the next token is
+/// needed, so large inputs or interactive inputs (such as a REPL reading from stdin) can be lexed
+/// without reading the whole input first. The NFA state and language scopes are kept between
+/// tokens.
+///
+/// By default, the lexer returns the first error found and then stops. With
+/// [`recover`](Self::recover), it behaves like [`tokenize_recover`] and returns every error
+/// alongside the recovered tokens
+pub struct Lexer<'a, B: Iterator<Item = u8>> {
+	/// Input bytes
+	bytes: PeekBytes<B>,
+	/// Custom language buffer for `!!` language switches
+	buf: &'a [LanguageTuple<'a>],
+	/// Running in-text position
+	pos: RunningPosition,
+	/// NFA parser for the current token
+	tree: NFABranch<'a>,
+	/// Language active outside each open curly brace
+	language_scopes: Vec<LanguageTupleRef<'a>>,
+	/// Number of open curly braces outside each open string interpolation, the start of its
+	/// string, and the position of its `{` until a token other than a comment or line break is
+	/// found inside it
+	interpolations: Vec<(usize, Position, Option<Position>)>,
+	/// Active language and tables
+	current_lang: LanguageTupleRef<'a>,
+	/// Lexed tokens and errors waiting to be returned
+	queue: VecDeque<Result<Token, LexError>>,
+	/// Continue after errors
+	recover: bool,
+	/// Emit [`TokType::LanguageSwitch`] tokens
+	switches: bool,
+	/// Input is exhausted or an error stopped lexing
+	done: bool,
+	/// Error that ended the input early, checked when the input ends
+	read_error: fn(&B) -> Option<String>,
+}
+
+impl<'a, B: Iterator<Item = u8>> Lexer<'a, B> {
+	/// Make a new lexer over a byte iterator
+	pub fn new(
+		bytes: B,
+		l: &'a LanguageRaw<'a>,
+		buf: &'a [LanguageTuple<'a>],
+		tables: (&'a dyn Table<u16>, &'a dyn Table<u8>, &'a dyn Table<u8>),
+	) -> Self {
+		let pos = RunningPosition::new();
+		Self {
+			bytes: PeekBytes { bytes, peeked: None, record: None },
+			buf, pos,
+			tree: NFABranch::new(Vec::new(), pos),
+			language_scopes: Vec::new(),
+			interpolations: Vec::new(),
+			current_lang: (l, tables),
+			queue: VecDeque::new(),
+			recover: false,
+			switches: true,
+			done: false,
+			read_error: |_| None,
+		}
+	}
+	
+	/// Set if the lexer continues after errors. See [`tokenize_recover`]
+	pub fn recover(mut self, recover: bool) -> Self {
+		self.recover = recover;
+		self
+	}
+	
+	/// Set if the lexer emits a [`TokType::LanguageSwitch`] token for each `!!` language switch.
+	/// This is on by default
+	pub fn language_switches(mut self, switches: bool) -> Self {
+		self.switches = switches;
+		self
+	}
+	
+	/// Input the lexer is reading from
+	pub fn source(&self) -> &B {
+		&self.bytes.bytes
+	}
+	
+	/// Lex from the next byte up to the end of the token it starts. Lexed tokens and errors are
+	/// pushed to `self.queue`
+	fn step(&mut self) {
+		use crate::tok::consts::*;
+		
+		let buf = self.buf;
+		let recover = self.recover;
+		let switches = self.switches;
+		let read_error = self.read_error;
+		let Self { bytes, pos, tree, language_scopes, interpolations, current_lang, queue, done, .. } = self;
+		
+		/// Push a lexed token to the queue. A number directly followed by a numeric type keyword is
+		/// pushed as a typed literal
+		macro_rules! emit {
+		    ($t:expr) => {{
+				let t = Token::from(&$t);
+				if !matches!(t.tt, TokType::NewLine(_) | TokType::Comment(..) | TokType::DocComment(..) | TokType::LanguageSwitch(_)) {
+					if let Some((_, _, brace)) = interpolations.last_mut() { *brace = None }
+				}
+				let typed = match queue.back_mut() {
+					Some(Ok(last)) => last.add_suffix(&t),
+					_ => false
+				};
+				if !typed { queue.push_back(Ok(t)) }
+			}};
+		}
+		
+		/// Make a new [`LexError`] variant with the current language code
+		macro_rules! error {
+		    ($v:ident { $($f:ident $(: $e:expr)?),*$(,)? }) => {
+				LexError::$v { $($f $(: $e)?,)* lang: current_lang.0.name.1.to_string() }
+			};
+		}
+		
+		/// Push the error to the queue and stop lexing, unless recovering
+		macro_rules! fail {
+		    ($e:expr) => {{
+				let e = $e;
+				queue.push_back(Err(e));
+				if !recover {
+					*done = true;
+					return
+				}
+			}};
+		}
+		
+		/// Skip bytes up to the next whitespace or curly brace and push them as an error token
+		/// starting at `$ps`
+		macro_rules! resync {
+		    ($ps:expr, $skipped:expr) => {{
+				let mut skipped = $skipped;
+				while let Some(t) = bytes.peek() {
+					if matches!(t, SPACE | TAB | NEWLINE | CARRIAGE_RETURN | OCB | CCB) { break }
+					bytes.next();
+					pos.advance(t);
+					skipped.push(t)
+				}
+				emit!(PreToken {
+					ps: $ps, pe: pos.finish(),
+					tt: PreTokType::Error(skipped)
+				});
+				tree.reset(pos);
+			}};
+		}
+		
+		/// Push a string literal starting at `$ps` and its errors. `$open` is the opening delimiter.
+		/// The string is pushed as `$closed` if it ends, or `$interp` if it stops at an
+		/// interpolation. `$start` is the start of the whole string if `$ps` is the start of a part
+		macro_rules! string {
+		    ($ps:expr, $lit:expr, $open:expr) => { string!($ps, $ps, $lit, $open, PreTokType::String, PreTokType::StringStart) };
+		    ($ps:expr, $start:expr, $lit:expr, $open:expr, $closed:path, $interp:path) => {{
+				let StringLit { bytes: mut contents, closed, interpolation, escapes } = $lit;
+				for (p, b) in escapes { fail!(error!(InvalidEscape { pos: p, bytes: b })) }
+				if let Some(brace) = interpolation {
+					emit!(PreToken { ps: $ps, pe: brace, tt: $interp(contents) });
+					emit!(PreToken { ps: brace, pe: pos.finish(), tt: PreTokType::InterpStart });
+					interpolations.push((language_scopes.len(), $start, Some(brace)));
+					language_scopes.push(*current_lang);
+				} else {
+					let tt = if closed {
+						$closed(contents)
+					} else {
+						fail!(error!(UnterminatedString { pos: $start }));
+						contents.splice(0..0, $open);
+						PreTokType::Error(contents)
+					};
+					emit!(PreToken { ps: $ps, pe: pos.finish(), tt });
+				}
+				tree.reset(pos);
+			}};
+		}
+		
+		/// Recover from an NFA failure, keeping any tokens matched before the failing token
+		macro_rules! nfa_fail {
+		    () => {{
+				fail!(error!(UnexpectedBytes { pos: tree.ps, bytes: tree.matched.clone() }));
+				for t in std::mem::take(&mut tree.preceding) { emit!(t) }
+				resync!(tree.ps, std::mem::take(&mut tree.matched));
+			}};
+		}
+		
+		macro_rules! propagate_check {
+		    ($t:tt) => {match tree.propagate($t, current_lang.0, current_lang.1.0, current_lang.1.1, current_lang.1.2) {
+					NFAPropRes::Continue => {}
+					NFAPropRes::End => {
+						for t in &tree.preceding { emit!(*t) }
+						tree.reset(pos)
+					}
+					NFAPropRes::Error => {
+						if let Some(branch) = &tree.branch {
+							*tree = *branch.clone()
+						} else {
+							nfa_fail!();
+							return
+						}
+					}
+				}};
+		}
+		
+		let b = match bytes.next() {
+			Some(b) => b,
+			None => {
+				*done = true;
+				if let Some(error) = read_error(&bytes.bytes) {
+					fail!(error!(ReadError { pos: pos.finish(), error }))
+				}
+				match tree.end() {
+					Some(rem) => for t in rem { emit!(t) },
+					None => {
+						fail!(error!(UnexpectedBytes { pos: tree.ps, bytes: tree.matched.clone() }));
+						for t in std::mem::take(&mut tree.preceding) { emit!(t) }
+						emit!(PreToken { ps: tree.ps, pe: pos.finish(), tt: PreTokType::Error(tree.matched.clone()) });
+					},
+				}
+				// strings with an interpolation that is never closed
+				for (_, start, _) in std::mem::take(interpolations) {
+					fail!(error!(UnterminatedString { pos: start }))
+				}
+				return
+			}
+		};
+		match b {
+			EXCLAMATION_MARK => {
+				match bytes.peek() {
+					Some(EXCLAMATION_MARK) => {
+						bytes.next();
+						let ps = pos.finish();
+						pos.advance(EXCLAMATION_MARK);
+						pos.advance(EXCLAMATION_MARK);
+						let mut lang_bytes = Vec::new();
+						let mut pe = pos.finish();
+						while let Some(b) = bytes.next() {
+							pe = pos.finish();
+							pos.advance(b);
+							if b == NEWLINE || b == SEMICOLON { break }
+							lang_bytes.push(b)
+						}
+						// the `\r` of a CRLF line ending isn't part of the language
+						let code = lang_bytes.strip_suffix(&[CARRIAGE_RETURN]).unwrap_or(&lang_bytes).to_vec();
+						let found = std::str::from_utf8(&code).map_err(|_| LookupError::NotFound(String::new()))
+							.and_then(|t| crate::get(t, buf));
+						match found {
+							Ok(ltr) => {
+								*current_lang = ltr;
+								if switches {
+									emit!(PreToken {
+										ps, pe: pos.finish(),
+										tt: PreTokType::LanguageSwitch(ltr.0.name.1.to_string())
+									})
+								}
+							}
+							Err(e) => {
+								match e {
+									LookupError::Ambiguous { codes, .. } => fail!(error!(AmbiguousLanguage { pos: ps, code, codes })),
+									LookupError::NotFound(_) => fail!(error!(UnknownLanguage { pos: ps, code })),
+								}
+								let mut skipped = vec![EXCLAMATION_MARK; 2];
+								skipped.append(&mut lang_bytes);
+								emit!(PreToken { ps, pe, tt: PreTokType::Error(skipped) });
+							}
+						}
+						tree.ps = pos.finish();
+						tree.pos = *pos;
+						return
+					}
+					Some(TAB) | Some(SPACE) => {
+						let ps = pos.finish();
+						pos.advance(EXCLAMATION_MARK);
+						emit!(PreToken {
+							ps, pe: pos.finish(),
+							tt: PreTokType::Not
+						});
+						tree.reset(pos);
+						return
+					}
+					_ => {
+						pos.advance(EXCLAMATION_MARK);
+						propagate_check!(EXCLAMATION_MARK);
+					},
+				}
+			}
+			OCB => {
+				// new scope
+				language_scopes.push(*current_lang);
+				pos.advance(OCB);
+				emit!(PreToken {
+					ps: tree.ps, pe: pos.finish(),
+					tt: PreTokType::LParenCurly
+				});
+				tree.ps = pos.finish();
+				tree.pos = *pos;
+				return
+			}
+			CCB => {
+				if interpolations.last().is_some_and(|(t, _, _)| t + 1 == language_scopes.len()) {
+					// end interpolation and continue the string
+					let (_, start, brace) = interpolations.pop().unwrap();
+					if let Some(brace) = brace { fail!(error!(EmptyInterpolation { pos: brace })) }
+					*current_lang = language_scopes.pop().unwrap();
+					pos.advance(CCB);
+					emit!(PreToken { ps: tree.ps, pe: pos.finish(), tt: PreTokType::InterpEnd });
+					tree.reset(pos);
+					let mut lit = StringLit::default();
+					lex_string_part(bytes, pos, false, 0, &mut lit);
+					string!(tree.ps, start, lit, Vec::new(), PreTokType::StringPart, PreTokType::StringPart);
+					return
+				}
+				// end scope
+				let tt = if let Some(l) = language_scopes.pop() {
+					*current_lang = l;
+					PreTokType::RParenCurly
+				} else {
+					fail!(error!(UnmatchedBrace { pos: pos.finish() }));
+					PreTokType::Error(vec![CCB])
+				};
+				pos.advance(CCB);
+				emit!(PreToken {
+					ps: tree.ps, pe: pos.finish(), tt
+				});
+				tree.ps = pos.finish();
+				tree.pos = *pos;
+				return
+			}
+			TAB | SPACE | CARRIAGE_RETURN => {
+				pos.advance(b);
+				tree.ps = pos.finish();
+				tree.pos = *pos;
+				return
+			},
+			BACKSLASH => {
+				pos.advance(BACKSLASH);
+				match bytes.peek() {
+					Some(BACKSLASH) => {
+						bytes.next();
+						pos.advance(BACKSLASH);
+						let doc = bytes.peek() == Some(BACKSLASH);
+						if doc {
+							bytes.next();
+							pos.advance(BACKSLASH);
+						}
+						let mut matched = Vec::new();
+						while let Some(n) = bytes.next() {
+							pos.advance(n);
+							if n == NEWLINE { break }
+							matched.push(n);
+						}
+						if matched.last() == Some(&CARRIAGE_RETURN) { matched.pop(); }
+						let lang = current_lang.0.name.1.to_string();
+						emit!(PreToken {
+							ps: tree.ps,
+							pe: pos.finish(),
+							tt: if doc { PreTokType::DocComment(lang, matched) } else { PreTokType::Comment(lang, matched) },
+						});
+						tree.reset(pos);
+						return
+					}
+					Some(STAR) => {
+						bytes.next();
+						pos.advance(STAR);
+						let mut doc = false;
+						let mut closed = false;
+						if bytes.peek() == Some(STAR) {
+							bytes.next();
+							pos.advance(STAR);
+							// `\**\` is an empty comment rather than the start of a doc comment
+							if bytes.peek() == Some(BACKSLASH) {
+								bytes.next();
+								pos.advance(BACKSLASH);
+								closed = true
+							} else {
+								doc = true
+							}
+						}
+						let mut matched = Vec::new();
+						// nested block comments are kept in the comment text
+						let mut depth = 1;
+						while !closed {
+							let Some(n) = bytes.next() else { break };
+							pos.advance(n);
+							match (n, bytes.peek()) {
+								(STAR, Some(BACKSLASH)) => {
+									bytes.next();
+									pos.advance(BACKSLASH);
+									depth -= 1;
+									if depth == 0 { closed = true } else { matched.extend([STAR, BACKSLASH]) }
+								}
+								(BACKSLASH, Some(STAR)) => {
+									bytes.next();
+									pos.advance(STAR);
+									depth += 1;
+									matched.extend([BACKSLASH, STAR])
+								}
+								_ => matched.push(n)
+							}
+						}
+						if !closed {
+							fail!(error!(UnterminatedComment { pos: tree.ps }));
+							let mut skipped = vec![BACKSLASH, STAR];
+							if doc { skipped.push(STAR) }
+							skipped.append(&mut matched);
+							emit!(PreToken { ps: tree.ps, pe: pos.finish(), tt: PreTokType::Error(skipped) });
+							tree.reset(pos);
+							return
+						}
+						let lang = current_lang.0.name.1.to_string();
+						emit!(PreToken {
+							ps: tree.ps,
+							pe: pos.finish(),
+							tt: if doc { PreTokType::DocComment(lang, matched) } else { PreTokType::Comment(lang, matched) },
+						});
+						tree.reset(pos);
+						return
+					}
+					t => {
+						fail!(error!(InvalidComment { pos: tree.ps, byte: t }));
+						resync!(tree.ps, vec![BACKSLASH]);
+						return
+					},
+				}
+			}
+			DOUBLE_QUOTE => {
+				pos.advance(DOUBLE_QUOTE);
+				string!(tree.ps, lex_string(bytes, pos, None), vec![DOUBLE_QUOTE]);
+				return
+			}
+			SINGLE_QUOTE => {
+				pos.advance(SINGLE_QUOTE);
+				let mut read = Vec::new();
+				let c = if bytes.peek() == Some(BACKSLASH) {
+					let ps = pos.finish();
+					bytes.next();
+					pos.advance(BACKSLASH);
+					read.push(BACKSLASH);
+					match parse_escape(bytes, pos, &mut read) {
+						Some(c) => c,
+						None => {
+							fail!(error!(InvalidEscape { pos: ps, bytes: read.clone() }));
+							read.insert(0, SINGLE_QUOTE);
+							resync!(tree.ps, read);
+							return
+						}
+					}
+				} else {
+					match parse_char(&mut *bytes, pos) {
+						Ok(c) => {
+							read.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+							c
+						}
+						Err(mut bytes) => {
+							fail!(error!(InvalidChar { pos: tree.ps, bytes: bytes.clone() }));
+							bytes.insert(0, SINGLE_QUOTE);
+							resync!(tree.ps, bytes);
+							return
+						}
+					}
+				};
+				if bytes.peek() != Some(SINGLE_QUOTE) {
+					let mut err_bytes = read.clone();
+					err_bytes.extend(bytes.peek());
+					fail!(error!(InvalidChar { pos: tree.ps, bytes: err_bytes }));
+					read.insert(0, SINGLE_QUOTE);
+					resync!(tree.ps, read);
+					return
+				}
+				bytes.next();
+				pos.advance(SINGLE_QUOTE);
+				emit!(PreToken {
+					ps: tree.ps,
+					pe: pos.finish(),
+					tt: PreTokType::Char(c)
+				});
+				tree.ps = pos.finish();
+				tree.pos = *pos;
+				return
+			}
+			_ => {
+				pos.advance(b);
+				propagate_check!(b);
+			}
+		}
+		while let Some(t) = bytes.peek() {
+			macro_rules! inside {
+			    ($t:ident, $($v:expr),+) => {$($t == $v)||+};
+			}
+			if (t == DOUBLE_QUOTE || t == HASH) && tree.matched == [b'r'] {
+				// raw string
+				for t in std::mem::take(&mut tree.preceding) { emit!(t) }
+				let ps = tree.ps;
+				let mut open = vec![b'r'];
+				while bytes.peek() == Some(HASH) {
+					bytes.next();
+					pos.advance(HASH);
+					open.push(HASH)
+				}
+				if bytes.peek() != Some(DOUBLE_QUOTE) {
+					fail!(error!(UnexpectedBytes { pos: ps, bytes: open.clone() }));
+					resync!(ps, open);
+					return
+				}
+				bytes.next();
+				pos.advance(DOUBLE_QUOTE);
+				let hashes = open.len() - 1;
+				open.push(DOUBLE_QUOTE);
+				string!(ps, lex_string(bytes, pos, Some(hashes)), open);
+				return
+			}
+			if inside!(t, SPACE, TAB, CARRIAGE_RETURN, OCB, CCB, BACKSLASH, DOUBLE_QUOTE, SINGLE_QUOTE) {
+				match tree.end() {
+					Some(rem) => for t in rem { emit!(t) },
+					None => {
+						fail!(error!(UnexpectedBytes { pos: tree.ps, bytes: tree.matched.clone() }));
+						for t in std::mem::take(&mut tree.preceding) { emit!(t) }
+						emit!(PreToken { ps: tree.ps, pe: pos.finish(), tt: PreTokType::Error(tree.matched.clone()) });
+					},
+				}
+				tree.reset(pos);
+				break
+			}
+			bytes.next();
+			pos.advance(t);
+			match tree.propagate(t, current_lang.0, current_lang.1.0, current_lang.1.1, current_lang.1.2) {
+				NFAPropRes::Continue => {}
+				NFAPropRes::End => {
+					for t in &tree.preceding { emit!(*t) }
+					tree.preceding.clear();
+					tree.branch = None;
+					tree.matched.clear();
+					tree.pos = *pos;
+					tree.ps = pos.finish();
+					break
+				}
+				NFAPropRes::Error => {
+					if let Some(branch) = &tree.branch {
+						*tree = *branch.clone()
+					} else {
+						nfa_fail!();
+						return
+					}
+				}
+			}
+		}
+	}
+}
+
+impl<'a, R: Read> Lexer<'a, ReadBytes<R>> {
+	/// Make a new lexer over a reader. The reader is wrapped in a [`BufReader`]
+	///
+	/// Reading stops at the first I/O error, which is returned as a [`LexError::ReadError`]. The
+	/// original error can be checked with [`ReadBytes::error`] through [`source`](Self::source)
+	pub fn from_reader(
+		reader: R,
+		l: &'a LanguageRaw<'a>,
+		buf: &'a [LanguageTuple<'a>],
+		tables: (&'a dyn Table<u16>, &'a dyn Table<u8>, &'a dyn Table<u8>),
+	) -> Self {
+		let mut lexer = Self::new(ReadBytes { bytes: BufReader::new(reader).bytes(), error: None }, l, buf, tables);
+		lexer.read_error = |b| b.error().map(ToString::to_string);
+		lexer
+	}
+}
+
+impl<'a, 'b> Lexer<'a, std::iter::Copied<std::slice::Iter<'b, u8>>> {
+	/// Make a new lexer over a byte slice
+	pub fn from_slice(
+		bytes: &'b [u8],
+		l: &'a LanguageRaw<'a>,
+		buf: &'a [LanguageTuple<'a>],
+		tables: (&'a dyn Table<u16>, &'a dyn Table<u8>, &'a dyn Table<u8>),
+	) -> Self {
+		Self::new(bytes.iter().copied(), l, buf, tables)
+	}
+}
+
+impl<B: Iterator<Item = u8>> Iterator for Lexer<'_, B> {
+	type Item = Result<Token, LexError>;
+	
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(t) = self.queue.pop_front() { return Some(t) }
+			if self.done { return None }
+			self.step()
+		}
+	}
+}
+
+/// # Reader bytes
+///
+/// Byte iterator over an [`io::Read`](Read) used by [`Lexer::from_reader`]. Iteration ends at
+/// the end of the reader or at the first I/O error
+pub struct ReadBytes<R: Read> {
+	bytes: std::io::Bytes<BufReader<R>>,
+	error: Option<std::io::Error>,
+}
+
+impl<R: Read> ReadBytes<R> {
+	/// I/O error that ended reading, if any
+	pub fn error(&self) -> Option<&std::io::Error> {
+		self.error.as_ref()
+	}
+}
+
+impl<R: Read> Iterator for ReadBytes<R> {
+	type Item = u8;
+	
+	fn next(&mut self) -> Option<u8> {
+		if self.error.is_some() { return None }
+		match self.bytes.next()? {
+			Ok(b) => Some(b),
+			Err(e) => {
+				self.error = Some(e);
+				None
+			}
+		}
+	}
+}
+
+/// Peekable byte iterator that keeps access to the underlying iterator
+struct PeekBytes<B: Iterator<Item = u8>> {
+	bytes: B,
+	peeked: Option<Option<u8>>,
+	/// Consumed bytes. Only kept for [lossless lexing](Lexer::lossless)
+	record: Option<VecDeque<u8>>,
+}
+
+impl<B: Iterator<Item = u8>> PeekBytes<B> {
+	/// Get the next byte without consuming it
+	fn peek(&mut self) -> Option<u8> {
+		let bytes = &mut self.bytes;
+		*self.peeked.get_or_insert_with(|| bytes.next())
+	}
+}
+
+impl<B: Iterator<Item = u8>> Iterator for PeekBytes<B> {
+	type Item = u8;
+	
+	fn next(&mut self) -> Option<u8> {
+		let out = match self.peeked.take() {
+			Some(t) => t,
+			None => self.bytes.next(),
+		};
+		if let (Some(b), Some(record)) = (out, &mut self.record) { record.push_back(b) }
+		out
+	}
+}
+
+/// # Parse character
+///
+/// Parse a UTF-8 valid character from a byte-stream. If this fails, the bytes read are returned.
+/// `pos` is advanced past every byte read
+fn parse_char<B: Iterator<Item = u8>>(mut bytes: B, pos: &mut RunningPosition) -> Result<char, Vec<u8>> {
+	let mut read = Vec::with_capacity(4);
+	let remaining = match bytes.next() {
+		Some(t @ 0..=127) => { read.push(t); 0 }
+		Some(t @ 192..=223) => { read.push(t); 1 }
+		Some(t @ 224..=239) => { read.push(t); 2 }
+		Some(t @ 240..=247) => { read.push(t); 3 }
+		Some(t) => {
+			pos.advance(t);
+			return Err(vec![t])
+		}
+		None => return Err(read),
+	};
+	pos.advance(read[0]);
+	
+	for _ in 0..remaining {
+		match bytes.next() {
+			Some(t @ 128..=191) => {
+				read.push(t);
+				pos.advance(t);
+			},
+			Some(t) => {
+				read.push(t);
+				pos.advance(t);
+				return Err(read)
+			}
+			None => return Err(read),
+		}
+	}
+	match std::str::from_utf8(&read) {
+		Ok(s) => s.chars().next().ok_or(read),
+		Err(_) => Err(read)
+	}
+}
+
+/// # String literal
+///
+/// String literal read by [`lex_string`]
+#[derive(Default)]
+struct StringLit {
+	/// String contents with escapes replaced
+	bytes: Vec<u8>,
+	/// Closing quote was found
+	closed: bool,
+	/// Position of the `{` the string stopped at to start an interpolation
+	interpolation: Option<Position>,
+	/// Invalid escapes with the position of their backslash
+	escapes: Vec<(Position, Vec<u8>)>,
+}
+
+/// # Lex string
+///
+/// Read a string literal after its opening quote. Raw strings have `Some` number of `#` around
+/// their quotes and no escapes. See [`TokType::String`] for the string forms
+fn lex_string<B: Iterator<Item = u8>>(bytes: &mut PeekBytes<B>, pos: &mut RunningPosition, hashes: Option<usize>) -> StringLit {
+	use crate::tok::consts::*;
+	
+	let raw = hashes.is_some();
+	let hashes = hashes.unwrap_or(0);
+	let mut lit = StringLit::default();
+	// `""` is either an empty string or the start of a block
+	if bytes.peek() == Some(DOUBLE_QUOTE) {
+		bytes.next();
+		pos.advance(DOUBLE_QUOTE);
+		if bytes.peek() == Some(DOUBLE_QUOTE) {
+			bytes.next();
+			pos.advance(DOUBLE_QUOTE);
+			lex_block(bytes, pos, raw, hashes, &mut lit);
+			return lit
+		}
+		if close_hashes(bytes, pos, hashes, 1, &mut lit.bytes) {
+			lit.closed = true;
+			return lit
+		}
+	}
+	lex_string_part(bytes, pos, raw, hashes, &mut lit);
+	lit
+}
+
+/// Read a single line string up to its closing quote, or up to a `{` starting an interpolation if
+/// the string isn't raw
+fn lex_string_part<B: Iterator<Item = u8>>(bytes: &mut PeekBytes<B>, pos: &mut RunningPosition, raw: bool, hashes: usize, lit: &mut StringLit) {
+	use crate::tok::consts::*;
+	
+	while let Some(t) = bytes.next() {
+		let ps = pos.finish();
+		pos.advance(t);
+		match t {
+			DOUBLE_QUOTE => if close_hashes(bytes, pos, hashes, 1, &mut lit.bytes) {
+				lit.closed = true;
+				break
+			},
+			OCB if !raw => {
+				lit.interpolation = Some(ps);
+				break
+			}
+			BACKSLASH if !raw => escape(bytes, pos, ps, &mut lit.bytes, &mut lit.escapes),
+			_ => lit.bytes.push(t),
+		}
+	}
+}
+
+/// # Lex block
+///
+/// Read a multi-line string block after its opening `"""`. Whitespace after the opening quotes is
+/// skipped, and the block starts on the next line. The block ends at a `"""` with only whitespace
+/// before it on its line. That whitespace is removed from the start of every line in the block,
+/// and lines indented less than the closing quotes keep the rest of their text. `\r\n` line
+/// endings become `\n`
+fn lex_block<B: Iterator<Item = u8>>(bytes: &mut PeekBytes<B>, pos: &mut RunningPosition, raw: bool, hashes: usize, lit: &mut StringLit) {
+	use crate::tok::consts::*;
+	
+	while let Some(t @ (SPACE | TAB | CARRIAGE_RETURN)) = bytes.peek() {
+		bytes.next();
+		pos.advance(t)
+	}
+	if bytes.peek() == Some(NEWLINE) {
+		bytes.next();
+		pos.advance(NEWLINE)
+	}
+	// lines with the number of whitespace bytes they start with
+	let mut lines = Vec::new();
+	let mut line = Vec::new();
+	let mut indent = 0;
+	while let Some(t) = bytes.next() {
+		let ps = pos.finish();
+		pos.advance(t);
+		let blank = indent == line.len();
+		match t {
+			NEWLINE => {
+				if line.last() == Some(&CARRIAGE_RETURN) { line.pop(); }
+				lines.push((std::mem::take(&mut line), indent));
+				indent = 0
+			}
+			SPACE | TAB if blank => {
+				line.push(t);
+				indent += 1
+			}
+			DOUBLE_QUOTE if blank && bytes.peek() == Some(DOUBLE_QUOTE) => {
+				bytes.next();
+				pos.advance(DOUBLE_QUOTE);
+				if bytes.peek() != Some(DOUBLE_QUOTE) {
+					line.extend([DOUBLE_QUOTE; 2]);
+					continue
+				}
+				bytes.next();
+				pos.advance(DOUBLE_QUOTE);
+				if close_hashes(bytes, pos, hashes, 3, &mut line) {
+					lit.closed = true;
+					break
+				}
+			}
+			BACKSLASH if !raw => escape(bytes, pos, ps, &mut line, &mut lit.escapes),
+			_ => line.push(t),
+		}
+	}
+	for (n, (l, lead)) in lines.iter().enumerate() {
+		if n != 0 { lit.bytes.push(NEWLINE) }
+		let strip = if lit.closed { l[..*lead].iter().zip(&line).take_while(|(a, b)| a == b).count() } else { 0 };
+		lit.bytes.extend(&l[strip..])
+	}
+	if !lit.closed {
+		if !lines.is_empty() { lit.bytes.push(NEWLINE) }
+		lit.bytes.append(&mut line)
+	}
+}
+
+/// Read the `#` after a closing quote of a raw string. If there are fewer than `hashes`, the
+/// `quotes` quotes and the `#` read are pushed to `out` and `false` is returned
+fn close_hashes<B: Iterator<Item = u8>>(bytes: &mut PeekBytes<B>, pos: &mut RunningPosition, hashes: usize, quotes: usize, out: &mut Vec<u8>) -> bool {
+	use crate::tok::consts::*;
+	
+	let mut count = 0;
+	while count < hashes && bytes.peek() == Some(HASH) {
+		bytes.next();
+		pos.advance(HASH);
+		count += 1
+	}
+	if count == hashes { return true }
+	out.extend(std::iter::repeat_n(DOUBLE_QUOTE, quotes));
+	out.extend(std::iter::repeat_n(HASH, count));
+	false
+}
+
+/// Read an escape after a backslash at `ps` and push it to `out`, or push an error to `errors`
+fn escape<B: Iterator<Item = u8>>(bytes: &mut PeekBytes<B>, pos: &mut RunningPosition, ps: Position, out: &mut Vec<u8>, errors: &mut Vec<(Position, Vec<u8>)>) {
+	let mut read = vec![crate::tok::consts::BACKSLASH];
+	match parse_escape(bytes, pos, &mut read) {
+		Some(c) => out.extend(c.encode_utf8(&mut [0; 4]).as_bytes()),
+		None => errors.push((ps, read)),
+	}
+}
+
+/// # Parse escape
+///
+/// Parse an escape sequence after a backslash. The bytes read are pushed to `read`. If the escape
+/// is unknown or invalid, `None` is returned
+///
+/// | Escape | Character |
+/// |---|---|
+/// | `\n` | Newline |
+/// | `\t` | Tab |
+/// | `\r` | Carriage return |
+/// | `\0` | Null |
+/// | `\\` | Backslash |
+/// | `\"` | Double quote |
+/// | `\'` | Single quote |
+/// | `\{` | Open curly brace |
+/// | `\}` | Closed curly brace |
+/// | `\u{..}` | Unicode scalar value of 1 to 6 hex digits |
+fn parse_escape<B: Iterator<Item = u8>>(bytes: &mut PeekBytes<B>, pos: &mut RunningPosition, read: &mut Vec<u8>) -> Option<char> {
+	use crate::tok::consts::*;
+	
+	let c = match parse_char(&mut *bytes, pos) {
+		Ok(c) => c,
+		Err(mut b) => {
+			read.append(&mut b);
+			return None
+		}
+	};
+	read.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+	match c {
+		'n' => Some('\n'),
+		't' => Some('\t'),
+		'r' => Some('\r'),
+		'0' => Some('\0'),
+		'\\' | '"' | '\'' | '{' | '}' => Some(c),
+		'u' => {
+			if bytes.peek() != Some(OCB) { return None }
+			bytes.next();
+			pos.advance(OCB);
+			read.push(OCB);
+			let mut value = 0;
+			let mut digits = 0;
+			while let Some(t) = bytes.peek() {
+				let d = match (t as char).to_digit(16) {
+					Some(d) if digits < 6 => d,
+					_ => break
+				};
+				bytes.next();
+				pos.advance(t);
+				read.push(t);
+				value = value * 16 + d;
+				digits += 1
+			}
+			if bytes.peek() != Some(CCB) { return None }
+			bytes.next();
+			pos.advance(CCB);
+			read.push(CCB);
+			if digits == 0 { return None }
+			char::from_u32(value)
+		}
+		_ => None
+	}
+}
+
+/// # NFA parser
+///
+/// This struct represents one branch parsing an input through the given transition tables. Each
+/// branch parses one token at a time. When a matching token is found, a new child branch is made
+/// and assigned to `self.branch`. The current branch continues parsing (until unable to do so)
+/// to ensure the matched token is of maximal token length
+#[derive(Clone)]
+struct NFABranch<'a> {
+	/// Current row (state) the branch is on
+	row: u16,
+	/// Running in-text position
+	pos: RunningPosition,
+	/// Starting position of the current token being parsed
+	ps: Position,
+	/// Matched bytes
+	matched: Vec<u8>,
+	/// Tokens preceding the current position
+	preceding: Vec<PreToken<'a>>,
+	/// Optional child branch. Only one is needed since if we have a child branch and find a new
+	/// token, this is longer than the token matched to start the current child branch and a new
+	/// branch can replace it
+	branch: Option<Box<Self>>,
+}
+
+#[cfg(debug_assertions)]
+impl Debug for NFABranch<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let mut w = f.width().unwrap_or(0);
+		write!(f, "{}| row: {}, {:?}->{:?}, preceding: {:?}, matched: {:?}", " ".repeat(w), self.row, self.ps, self.pos, self.preceding, self.matched)?;
+		if let Some(child) = &self.branch {
+			w += 1;
+			write!(f, "\n{:w$?}", child)
+		} else { Ok(()) }
+	}
+}
+
+/// # NFA parsing result
+///
+/// |  | Reached accepting state | Has not |
+/// |---|---|---|
+/// | Has a next state | [`Continue`](Self::Continue) | [`Continue`](Self::Continue) |
+/// | Does not | [`End`](Self::End) | [`Error`](Self::Error) |
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+enum NFAPropRes {
+	/// Node has a next state to go to. Used if the node has branched or not
+	Continue,
+	/// Node has reached a terminating state and has no next state to go to
+	End,
+	/// Node has not reached a terminating state and has no next state to go to
+	Error,
+}
+
+impl<'a> NFABranch<'a> {
+	pub fn new(preceding: Vec<PreToken<'a>>, pos: RunningPosition) -> Self {
+		let ps = pos.finish();
+		Self {
+			preceding,
+			pos, ps,
+			row: 0,
+			matched: Vec::new(),
+			branch: None,
+		}
+	}
+	
+	fn new_branch(&mut self, new: PreToken<'a>) {
+		let mut preceding = self.preceding.clone();
+		preceding.push(new);
+		self.branch = Some(Box::new(NFABranch::new(preceding, self.pos.clone())));
+	}
+	
+	pub fn reset(&mut self, p: &RunningPosition) {
+		self.preceding.clear();
+		self.branch = None;
+		self.row = 0;
+		self.matched.clear();
+		self.ps = p.finish();
+		self.pos = p.clone();
+	}
+	
+	pub fn propagate(&mut self, b: u8, l: &'a LanguageRaw<'a>,
+		transition: &dyn Table<u16>, tt: &dyn Table<u8>, td: &dyn Table<u8>) -> NFAPropRes {
+		self.pos.advance(b);
+		let alternative = if let Some(ref mut branch) = self.branch {
+			match branch.propagate(b, l, transition, tt, td) {
+				NFAPropRes::Continue => false,
+				NFAPropRes::Error => {
+					self.branch = branch.branch.clone();
+					false
+				},
+				NFAPropRes::End => true
+			}
+		} else { false };
+		self.matched.push(b);
+		let last_row = self.row;
+		self.row = transition.element(self.row, b);
+		let tt_val = tt.element(last_row, b);
+		if self.row == 0 {
+			// end of branch i.e. the give byte has no next state in the NFA
+			if tt_val == 0 {
+				if alternative {
+					let branch = self.branch.clone();
+					*self = *branch.unwrap();
+					return NFAPropRes::End
+				}
+				// current state is not accepting. branch does not accept input
+				NFAPropRes::Error
+			} else {
+				// current state is accepting. branch accepts input
+				self.preceding.push(PreToken::new(
+					self.ps.clone(),
+					self.pos.finish(),
+					tt_val,
+					td.element(last_row, b),
+					self.matched.clone(),
+					l
+				));
+				NFAPropRes::End
+			}
+		} else {
+			// there is a next state for the given byte
+			if tt_val != 0 {
+				// this is a branching state i.e. this could be the end of the token/start of a new one
+				self.new_branch(
+					PreToken::new(
+						self.ps.clone(),
+						self.pos.finish(),
+						tt_val,
+						td.element(last_row, b),
+						self.matched.clone(),
+						l
+					)
+				)
+			}
+			NFAPropRes::Continue
+		}
+	}
+	
+	pub fn end(&self) -> Option<Vec<PreToken<'a>>> {
+		if self.row == 0 {
+			Some(self.preceding.clone())
+		} else {
+			if let Some(branch) = &self.branch {
+				branch.end()
+			} else {
+				None
+			}
+		}
+	}
+}
+
+/// QOL function to filter out comments
+///
+/// This is intended to be used with [`filter`](Iterator::filter) when parsing tokens into ASTs.
+/// [Doc comments](TokType::DocComment) are kept
+pub fn comments_filter(tok: &Token) -> bool {
+	match tok.tt {
+		TokType::Comment(_, _) => false,
+		_ => true
+	}
+}
+
+/// QOL function to filter out language switches
+///
+/// This is intended to be used with [`filter`](Iterator::filter) when parsing tokens into ASTs.
+/// See also [`Lexer::language_switches`]
+pub fn language_switch_filter(tok: &Token) -> bool {
+	!matches!(tok.tt, TokType::LanguageSwitch(_))
+}
@@ -15,11 +15,21 @@
     issue_tracker_base_url = "https://github.com/fck-language/lang/issues"
 )]
 
+mod confusable;
 mod lexer;
+pub mod load;
+mod lookup;
+mod render;
+mod translate;
 
 use lang_inner::compress::UStream;
 use lang_inner::{LanguageRaw, Table};
-pub use lexer::{tokenize, comments_filter};
+pub use lexer::{tokenize, tokenize_recover, tokenize_lossless, comments_filter, language_switch_filter, LexError, Lexer, ReadBytes};
+pub use lexer::{Checkpoint, IncrementalLexer, Lossless, LosslessToken, Trivia, TriviaKind};
+pub use lookup::LookupError;
+pub use confusable::{confusables, Confusable, ConfusableWith};
+pub use render::render;
+pub use translate::translate;
 pub mod tok;
 
 use lang_macros::languages;
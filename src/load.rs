@@ -0,0 +1,164 @@
+//! # Custom language loading
+//!
+//! Loads custom languages from fckl files at runtime. The loaded languages are used as the
+//! language buffer for [`get`](crate::get) and [`tokenize`](crate::tokenize) so custom languages
+//! can be used without recompiling.
+//!
+//! Languages can either be fckl files (`.fckl`) or precompiled
+//! [language packs](lang_inner::compress::pack) (`.fckp`). Packs hold the compressed tables so
+//! they load without generating them.
+//!
+//! The text of each loaded file is leaked to give a `'static` [`LanguageTuple`]. Languages are
+//! expected to be loaded once and kept for the life of the program. Files that fail to load are
+//! not leaked
+
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use lang_inner::{FcklError, LanguageRaw, verify::Finding};
+use lang_inner::compress::{Compress, UStream};
+use lang_inner::compress::pack::{LanguagePack, PackError};
+use lang_inner::tables::tabularize;
+use crate::LanguageTuple;
+
+/// # Language loading error
+///
+/// Each variant holds the path of the file that failed to load
+#[derive(Debug)]
+pub enum LoadError {
+	/// File or directory could not be read
+	Io {
+		/// Path of the file or directory
+		path: PathBuf,
+		/// Underlying error
+		error: std::io::Error,
+	},
+	/// File is not a valid language pack
+	Pack {
+		/// Path of the file
+		path: PathBuf,
+		/// Pack error
+		error: PackError,
+	},
+	/// File is not a valid fckl file
+	Parse {
+		/// Path of the file
+		path: PathBuf,
+		/// Parsing error
+		error: FcklError,
+	},
+	/// File failed [verification](LanguageRaw::verify)
+	Verify {
+		/// Path of the file
+		path: PathBuf,
+		/// Verification findings
+		findings: Vec<Finding>,
+	},
+}
+
+impl LoadError {
+	/// Path of the file or directory that failed to load
+	pub fn path(&self) -> &Path {
+		match self {
+			LoadError::Io { path, .. }
+			| LoadError::Pack { path, .. }
+			| LoadError::Parse { path, .. }
+			| LoadError::Verify { path, .. } => path,
+		}
+	}
+}
+
+impl Display for LoadError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: ", self.path().display())?;
+		match self {
+			LoadError::Io { error, .. } => write!(f, "{}", error),
+			LoadError::Pack { error, .. } => write!(f, "{}", error),
+			LoadError::Parse { error, .. } => write!(f, "{}", error),
+			LoadError::Verify { findings, .. } => {
+				write!(f, "failed verification")?;
+				for i in findings { write!(f, "\n- {}", i)? }
+				Ok(())
+			}
+		}
+	}
+}
+
+impl std::error::Error for LoadError {}
+
+/// Custom language directory. This is `fck/languages` in the user config directory given by
+/// [`dirs::config_dir`]
+pub fn config_dir() -> Option<PathBuf> {
+	dirs::config_dir().map(|t| t.join("fck").join("languages"))
+}
+
+/// Load all the custom languages in the [config directory](config_dir). See [`load_dir`]
+pub fn load_config() -> (Vec<LanguageTuple<'static>>, Vec<LoadError>) {
+	match config_dir() {
+		Some(dir) => load_dir(&dir),
+		None => (Vec::new(), Vec::new())
+	}
+}
+
+/// Load all the `.fckl` and `.fckp` files in a directory
+///
+/// Returns the languages that loaded and the errors for those that didn't, so one bad file does
+/// not stop the others from loading. A missing directory is treated as having no languages.
+/// Files are loaded in path order
+pub fn load_dir(dir: &Path) -> (Vec<LanguageTuple<'static>>, Vec<LoadError>) {
+	let mut out = Vec::new();
+	let mut errors = Vec::new();
+	let entries = match std::fs::read_dir(dir) {
+		Ok(entries) => entries,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (out, errors),
+		Err(error) => return (out, vec![LoadError::Io { path: dir.to_path_buf(), error }]),
+	};
+	let mut paths = Vec::new();
+	for entry in entries {
+		match entry {
+			Ok(entry) => {
+				let path = entry.path();
+				if path.is_file() && path.extension().is_some_and(|t| t == "fckl" || t == "fckp") {
+					paths.push(path)
+				}
+			}
+			Err(error) => errors.push(LoadError::Io { path: dir.to_path_buf(), error }),
+		}
+	}
+	paths.sort();
+	for path in paths {
+		match load_file(&path) {
+			Ok(l) => out.push(l),
+			Err(e) => errors.push(e),
+		}
+	}
+	(out, errors)
+}
+
+/// Load a single fckl file or language pack
+///
+/// The file is parsed and [verified](LanguageRaw::verify). For fckl files the transition tables
+/// are then generated and compressed. Files with a `.fckp` extension are read as language packs
+pub fn load_file(path: &Path) -> Result<LanguageTuple<'static>, LoadError> {
+	let io_err = |error| LoadError::Io { path: path.to_path_buf(), error };
+	let (text, tables) = if path.extension().is_some_and(|t| t == "fckp") {
+		let bytes = std::fs::read(path).map_err(io_err)?;
+		let pack = LanguagePack::from_bytes(&bytes)
+			.map_err(|error| LoadError::Pack { path: path.to_path_buf(), error })?;
+		(pack.text, Some(pack.tables))
+	} else {
+		(std::fs::read_to_string(path).map_err(io_err)?, None)
+	};
+	let parse_err = |error| LoadError::Parse { path: path.to_path_buf(), error };
+	// check the language before leaking the text so files that fail to load are freed
+	let findings = LanguageRaw::from_text(&text).map_err(parse_err)?.verify();
+	if !findings.is_empty() {
+		return Err(LoadError::Verify { path: path.to_path_buf(), findings })
+	}
+	let text: &'static str = Box::leak(text.into_boxed_str());
+	let lang = LanguageRaw::from_text(text).map_err(parse_err)?;
+	let tables = tables.unwrap_or_else(|| {
+		let (transition, tt, td) = tabularize(&lang);
+		(UStream::compress(&transition), UStream::compress(&tt), UStream::compress(&td))
+	});
+	Ok((lang, tables))
+}
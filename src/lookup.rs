@@ -0,0 +1,31 @@
+//! # Language lookup errors
+//!
+//! Contains the [`LookupError`] type returned by [`get`](crate::get) when a language can't be
+//! found
+
+use std::fmt::{Display, Formatter};
+
+/// # Language lookup error
+#[derive(Clone, PartialEq, Debug)]
+pub enum LookupError {
+	/// No language has the given code, name, or alias
+	NotFound(String),
+	/// More than one language has the given code, name, or alias
+	Ambiguous {
+		/// Name looked up
+		name: String,
+		/// Codes of the matching languages
+		codes: Vec<String>,
+	},
+}
+
+impl Display for LookupError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			LookupError::NotFound(name) => write!(f, "no language found for {:?}", name),
+			LookupError::Ambiguous { name, codes } => write!(f, "{:?} is ambiguous. Could be any of {}", name, codes.join(", ")),
+		}
+	}
+}
+
+impl std::error::Error for LookupError {}
@@ -0,0 +1,228 @@
+//! # Rendering
+//!
+//! Turn a token stream back into source. Tokens are spelt in a single language, and token
+//! positions are used to keep the original layout where possible
+
+use lang_inner::LanguageRaw;
+use num_bigint::BigUint;
+use crate::tok::{Arrow, Cmp, Decimal, NewLine, Op, PrimitiveKeyword, Token, TokType};
+use crate::translate::{digits, flip, spelling};
+
+/// Largest number of zeros a float is padded with before it's rendered with an exponent
+const MAX_PADDING: usize = 6;
+
+/// # Render a token stream
+///
+/// Turn tokens back into source in the language `l`. Keywords, booleans, and numbers use the
+/// spellings and digits of `l`, and round and square brackets are flipped if `l` is written right
+/// to left.
+///
+/// Token positions are used as layout hints. Line breaks between tokens are kept and each line is
+/// indented with spaces up to the column of its first token. Gaps between tokens on the same line
+/// are kept as spaces. A space is added between tokens that would otherwise be lexed as a single
+/// token, so tokens with no positions, such as generated tokens, still give valid source.
+///
+/// Some source details are not held by tokens and are normalised:
+/// - Numbers are written in base 10. Floats always have a decimal point, and use an exponent
+///   instead of long runs of zeros
+/// - Strings are written as `"..."` strings with escapes
+/// - Comments are written as line comments if they end on a later line or can't be put between
+///   block comment delimiters, and block comments otherwise
+/// - [Language switches](TokType::LanguageSwitch) are removed since everything is written in `l`
+pub fn render(toks: &[Token], l: &LanguageRaw<'_>) -> String {
+	let mut out = String::new();
+	let mut prev: Option<&Token> = None;
+	for (n, tok) in toks.iter().enumerate() {
+		let text = token(tok, toks.get(n + 1), l);
+		match prev {
+			Some(p) if tok.ps.ln > p.pe.ln => {
+				out.push_str(&"\n".repeat(tok.ps.ln - p.pe.ln));
+				out.push_str(&" ".repeat(tok.ps.char_col));
+			}
+			Some(p) if tok.ps.ln == p.pe.ln && tok.ps.char_col > p.pe.char_col => {
+				out.push_str(&" ".repeat(tok.ps.char_col - p.pe.char_col))
+			}
+			Some(p) => if let (Some(a), Some(b)) = (out.chars().last(), text.chars().next()) {
+				// an `r` identifier followed by a string would be lexed as a raw string
+				let raw = matches!(&p.tt, TokType::Identifier(_, i) if i == b"r") && matches!(b, '"' | '#');
+				if joins(a, b) || raw { out.push(' ') }
+			}
+			None => out.push_str(&" ".repeat(tok.ps.char_col)),
+		}
+		out.push_str(&text);
+		prev = Some(tok);
+	}
+	out
+}
+
+/// Check if two characters either side of a token boundary would be lexed as one token
+fn joins(a: char, b: char) -> bool {
+	const SYMBOLS: &str = "+-*/%=!<>:?.@";
+	let word = |c: char| c == '_' || c.is_alphanumeric();
+	(word(a) && word(b))
+		|| (SYMBOLS.contains(a) && SYMBOLS.contains(b))
+		|| (a.is_numeric() && b == '.')
+		|| (a == '.' && b.is_numeric())
+		// `"` then `"` would start an empty string or a block string
+		|| (a == '"' && b == '"')
+}
+
+/// Render a single token. `next` is used to end interpolated strings
+fn token(tok: &Token, next: Option<&Token>, l: &LanguageRaw<'_>) -> String {
+	let bracket = |b: u8| (if l.left_right { b } else { flip(b) } as char).to_string();
+	match &tok.tt {
+		TokType::Int(i, suffix) => number(i, l) + suffix_spelling(*suffix, l),
+		TokType::Float(f, suffix) => float(f, l) + suffix_spelling(*suffix, l),
+		TokType::String(s) => format!("\"{}\"", escape(s)),
+		TokType::StringStart(s) => format!("\"{}", escape(s)),
+		TokType::StringPart(s) => match next {
+			Some(Token { tt: TokType::InterpStart, .. }) => escape(s),
+			_ => format!("{}\"", escape(s)),
+		},
+		TokType::InterpStart | TokType::LParenCurly => "{".to_string(),
+		TokType::InterpEnd | TokType::RParenCurly => "}".to_string(),
+		TokType::Char(c) => match c {
+			'\'' => "'\\''".to_string(),
+			c => format!("'{}'", escape(c.to_string().as_bytes())),
+		},
+		TokType::Op(op) => op_str(op).to_string(),
+		TokType::Cmp(cmp) => match cmp {
+			Cmp::Eq => "==",
+			Cmp::NE => "!=",
+			Cmp::LT => "<",
+			Cmp::GT => ">",
+			Cmp::LTE => "<=",
+			Cmp::GTE => ">=",
+			// only used for matching tokens
+			Cmp::Any => "",
+		}.to_string(),
+		TokType::Increment => "++".to_string(),
+		TokType::Decrement => "--".to_string(),
+		TokType::LParen => bracket(b'('),
+		TokType::RParen => bracket(b')'),
+		TokType::LParenSquare => bracket(b'['),
+		TokType::RParenSquare => bracket(b']'),
+		TokType::Not => "!".to_string(),
+		TokType::Colon => ":".to_string(),
+		TokType::QuestionMark => "?".to_string(),
+		TokType::Dot => ".".to_string(),
+		TokType::Comma => ",".to_string(),
+		TokType::At => "@".to_string(),
+		TokType::Arrow(Arrow::Single) => "->".to_string(),
+		TokType::Arrow(Arrow::Double) => "=>".to_string(),
+		TokType::Set(op) => op.as_ref().map_or("", op_str).to_string() + "=",
+		TokType::NewLine(NewLine::Explicit) => ";".to_string(),
+		TokType::NewLine(NewLine::Implicit) => "\n".to_string(),
+		TokType::Identifier(_, i) | TokType::Error(i) => String::from_utf8_lossy(i).into_owned(),
+		TokType::Comment(_, c) => comment(tok, c, false),
+		TokType::DocComment(_, c) => comment(tok, c, true),
+		// line breaks after the switch come from the positions of the next token
+		TokType::LanguageSwitch(_) => String::new(),
+		tt => spelling(tt, l).unwrap_or_default().to_string(),
+	}
+}
+
+/// Spelling of an operator
+fn op_str(op: &Op) -> &'static str {
+	match op {
+		Op::Plus => "+",
+		Op::Minus => "-",
+		Op::Mod => "%",
+		Op::Mult => "*",
+		Op::Div => "/",
+		Op::Pow => "**",
+		// only used for matching tokens
+		Op::Any => "",
+	}
+}
+
+/// Spelling of a number type suffix
+fn suffix_spelling<'a>(suffix: Option<PrimitiveKeyword>, l: &LanguageRaw<'a>) -> &'a str {
+	suffix.and_then(|k| spelling(&TokType::PrimitiveKeyword(k), l)).unwrap_or_default()
+}
+
+/// Render a comment as a line comment if it ends on a later line or would change as a block
+/// comment, or a block comment otherwise
+fn comment(tok: &Token, c: &[u8], doc: bool) -> String {
+	let (line, block) = if doc { ("\\\\\\", "\\**") } else { ("\\\\", "\\*") };
+	let c = String::from_utf8_lossy(c);
+	if !c.contains('\n') && (tok.pe.ln > tok.ps.ln || !block_safe(&c, doc)) {
+		format!("{}{}\n", line, c)
+	} else {
+		format!("{}{}*\\", block, c)
+	}
+}
+
+/// Check if comment text is lexed back as the same comment between block comment delimiters.
+/// Nested block comments in the text must be closed, and the text can't turn the opening
+/// delimiter into a doc comment or `\**\`
+fn block_safe(c: &str, doc: bool) -> bool {
+	if c.starts_with(if doc { '\\' } else { '*' }) { return false }
+	let s = format!("{}*\\", c).into_bytes();
+	let mut depth = 1;
+	let mut i = 0;
+	while i < s.len() {
+		match (s[i], s.get(i + 1)) {
+			(b'*', Some(b'\\')) => {
+				depth -= 1;
+				i += 1;
+				if depth == 0 { return i + 1 == s.len() }
+			}
+			(b'\\', Some(b'*')) => {
+				depth += 1;
+				i += 1
+			}
+			_ => {}
+		}
+		i += 1
+	}
+	false
+}
+
+/// Localise base 10 digits
+fn localise(s: &str, l: &LanguageRaw<'_>) -> String {
+	let (_, d) = digits(&l.keywords.digits);
+	s.chars().map(|c| c.to_digit(10).map_or(c, |t| d[t as usize])).collect()
+}
+
+/// Render an integer in base 10
+fn number(i: &BigUint, l: &LanguageRaw<'_>) -> String {
+	localise(&i.to_string(), l)
+}
+
+/// Render a float with a decimal point, using an exponent if it would need more than
+/// [`MAX_PADDING`] zeros
+fn float(f: &Decimal, l: &LanguageRaw<'_>) -> String {
+	let mantissa = f.mantissa.to_string();
+	let point = mantissa.len() as isize + f.exponent;
+	let out = if f.exponent >= 0 && f.exponent as usize <= MAX_PADDING {
+		format!("{}{}.0", mantissa, "0".repeat(f.exponent as usize))
+	} else if f.exponent < 0 && point > 0 {
+		let (int, frac) = mantissa.split_at(point as usize);
+		format!("{}.{}", int, frac)
+	} else if f.exponent < 0 && -point as usize <= MAX_PADDING {
+		format!("0.{}{}", "0".repeat(-point as usize), mantissa)
+	} else {
+		format!("{}.0e{}", mantissa, f.exponent)
+	};
+	localise(&out, l)
+}
+
+/// Escape string or character contents
+fn escape(s: &[u8]) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in String::from_utf8_lossy(s).chars() {
+		match c {
+			'\n' => out.push_str("\\n"),
+			'\t' => out.push_str("\\t"),
+			'\r' => out.push_str("\\r"),
+			'\0' => out.push_str("\\0"),
+			'\\' | '"' | '{' | '}' => {
+				out.push('\\');
+				out.push(c)
+			}
+			c => out.push(c),
+		}
+	}
+	out
+}
@@ -7,6 +7,7 @@ use cflp::NodeData;
 use num_bigint::BigUint;
 use lang_inner::{Digits, LanguageRaw};
 use lang_macros::doc_see;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
 
 pub(crate) mod consts {
 	pub const TAB: u8 = 9;
@@ -18,9 +19,12 @@ pub(crate) mod consts {
 	pub const OCB: u8 = 123;
 	/// Closed curly brace `}`
 	pub const CCB: u8 = 125;
-	pub const FORWARD_SLASH: u8 = 92;
+	/// Backslash `\`
+	pub const BACKSLASH: u8 = 92;
 	pub const STAR: u8 = 42;
 	pub const DOUBLE_QUOTE: u8 = 34;
+	/// Hash `#` for raw strings
+	pub const HASH: u8 = 35;
 	pub const SINGLE_QUOTE: u8 = 39;
 	pub const CARRIAGE_RETURN: u8 = 13;
 }
@@ -36,27 +40,35 @@ pub struct RunningPosition {
 	ln: usize,
 	/// Column/byte number. Starting from 0
 	col: usize,
+	/// Absolute byte offset. Starting from 0
+	offset: usize,
+	/// Column in UTF-8 characters. Starting from 0
+	char_col: usize,
+	/// Column in UTF-16 code units. Starting from 0
+	utf16_col: usize,
 	/// Previous byte. Defaults to `0x00`
 	pub previous: u8,
 }
 
 /// # Position data
 ///
-/// Holds positional data on tokens and AST nodes
-#[derive(Copy, Clone, PartialEq)]
+/// Holds positional data on tokens and AST nodes.
+///
+/// Lines are ended by `\n`, so for `\r\n` line endings the `\r` is the last column of the line.
+/// The columns are given in bytes, characters, and UTF-16 code units since editors and editor
+/// protocols count columns differently
+#[derive(Copy, Clone, PartialEq, Default)]
 pub struct Position {
 	/// Line number. Starting from 0
 	pub ln: usize,
 	/// Columns/byte number. Starting from 0
 	pub col: usize,
-}
-
-impl Default for Position {
-	fn default() -> Self {
-		Self {
-			ln: 0, col: 0
-		}
-	}
+	/// Absolute byte offset. Can be used to slice the input. Starting from 0
+	pub offset: usize,
+	/// Column in UTF-8 characters. Starting from 0
+	pub char_col: usize,
+	/// Column in UTF-16 code units. Starting from 0
+	pub utf16_col: usize,
 }
 
 #[cfg(any(test, debug_assertions))]
@@ -67,26 +79,45 @@ impl Debug for Position {
 }
 
 impl RunningPosition {
-	/// Make a new instance of `Self`. Sets the [line](Self::ln), [column](Self::col), and
+	/// Make a new instance of `Self`. Sets all the positions and the
 	/// [previous byte](Self::previous) to 0
 	pub fn new() -> Self {
 		Self {
 			ln: 0,
 			col: 0,
+			offset: 0,
+			char_col: 0,
+			utf16_col: 0,
 			previous: 0,
 		}
 	}
 	
 	/// Advance the position on a given byte
+	///
+	/// The character and UTF-16 columns are advanced on the first byte of each UTF-8 character.
+	/// Bytes that aren't valid UTF-8 count as one character each
 	pub fn advance(&mut self, b: u8) {
+		self.offset += 1;
 		if b == consts::NEWLINE {
 			self.ln += 1;
-			self.col = 0
+			self.col = 0;
+			self.char_col = 0;
+			self.utf16_col = 0
 		} else {
-			self.col += 1
-		}
-		if b == consts::CARRIAGE_RETURN && self.previous == consts::NEWLINE {
-			self.col -= 1
+			self.col += 1;
+			match b {
+				// UTF-8 continuation byte
+				0x80..=0xbf => {}
+				// start of a 4 byte character. These are surrogate pairs in UTF-16
+				0xf0..=0xf7 => {
+					self.char_col += 1;
+					self.utf16_col += 2
+				}
+				_ => {
+					self.char_col += 1;
+					self.utf16_col += 1
+				}
+			}
 		}
 		self.previous = b
 	}
@@ -96,7 +127,36 @@ impl RunningPosition {
 		Position {
 			ln: self.ln,
 			col: self.col,
+			offset: self.offset,
+			char_col: self.char_col,
+			utf16_col: self.utf16_col,
+		}
+	}
+	
+	/// Move the position as if the text at `from` was moved to `to`. See [`Position::shift`]
+	pub(crate) fn shift(&mut self, from: Position, to: Position) {
+		let mut p = self.finish();
+		p.shift(from, to);
+		self.ln = p.ln;
+		self.col = p.col;
+		self.offset = p.offset;
+		self.char_col = p.char_col;
+		self.utf16_col = p.utf16_col
+	}
+}
+
+impl Position {
+	/// Move the position as if the text at `from` was moved to `to`. The position must be at or
+	/// after `from`. Positions on the same line as `from` keep their column offset from `from`,
+	/// and positions on later lines keep their column
+	pub(crate) fn shift(&mut self, from: Position, to: Position) {
+		if self.ln == from.ln {
+			self.col = self.col - from.col + to.col;
+			self.char_col = self.char_col - from.char_col + to.char_col;
+			self.utf16_col = self.utf16_col - from.utf16_col + to.utf16_col
 		}
+		self.ln = self.ln - from.ln + to.ln;
+		self.offset = self.offset - from.offset + to.offset
 	}
 }
 
@@ -138,6 +198,24 @@ impl From<&PreToken<'_>> for Token {
 	}
 }
 
+impl Token {
+	/// Add a type suffix to a number literal. Returns `false` if `suffix` is not a type keyword
+	/// directly after the literal that can be used as a suffix for it
+	pub(crate) fn add_suffix(&mut self, suffix: &Token) -> bool {
+		let TokType::PrimitiveKeyword(k) = suffix.tt else { return false };
+		if self.pe != suffix.ps { return false }
+		let slot = match &mut self.tt {
+			TokType::Int(_, slot) if k.is_numeric() => slot,
+			TokType::Float(_, slot) if k.is_float() => slot,
+			_ => return false
+		};
+		if slot.is_some() { return false }
+		*slot = Some(k);
+		self.pe = suffix.pe;
+		true
+	}
+}
+
 impl PartialEq<TokType> for Token {
 	fn eq(&self, other: &TokType) -> bool {
 		self.tt == *other
@@ -173,19 +251,50 @@ pub enum TokType {
 	/// - `tt=1`
 	/// - `td=2..=5`
 	///
-	/// See the `digits` module of [tables](lang_inner::tables) for `td` values
-	Int(BigUint),
+	/// See the `digits` module of [tables](lang_inner::tables) for `td` values. Holds the type
+	/// suffix if the literal is directly followed by a [numeric](PrimitiveKeyword::is_numeric)
+	/// type keyword, such as `10uint`
+	Int(BigUint, Option<PrimitiveKeyword>),
 	/// Float literal
 	/// - `tt=1`
-	/// - `td` is todo
-	Float(f64),
+	/// - `td=6`
+	///
+	/// Holds the type suffix if the literal is directly followed by a
+	/// [float](PrimitiveKeyword::is_float) type keyword, such as `1.5bfloat`
+	Float(Decimal, Option<PrimitiveKeyword>),
 	/// Boolean literal
 	/// - `tt=2`
 	/// - `td=0` for `false` and `td=1` for `true`
 	Bool(bool),
-	/// String literal
+	/// String literal. This can be any of
+	/// - `"..."` with escapes
+	/// - `r"..."`, `r#"..."#`, ... raw string with no escapes
+	/// - `"""` indented multi-line block, or `r"""` for a raw block. The closing `"""` goes on its
+	///   own line and its indentation is removed from every line
+	///
+	/// `"..."` strings containing an interpolation are lexed as a [`StringStart`](Self::StringStart)
+	/// instead. Raw strings and blocks have no interpolation
 	/// - `tt=3`
 	String(Vec<u8>),
+	/// Start of an interpolated string, holding the text up to the first interpolation. An
+	/// interpolated string `"a {b} c {d}"` is lexed as
+	/// 1. `StringStart("a ")`
+	/// 2. `InterpStart`, tokens for `b`, `InterpEnd`
+	/// 3. `StringPart(" c ")`
+	/// 4. `InterpStart`, tokens for `d`, `InterpEnd`
+	/// 5. `StringPart("")`
+	///
+	/// Every `InterpEnd` is followed by a `StringPart`, and the string ends at a `StringPart` not
+	/// followed by an `InterpStart`. Use `\{` and `\}` for literal braces
+	StringStart(Vec<u8>),
+	/// Text of an interpolated string after an interpolation. See [`StringStart`](Self::StringStart)
+	StringPart(Vec<u8>),
+	/// `{` starting an interpolation. The tokens up to the matching [`InterpEnd`](Self::InterpEnd)
+	/// are lexed in the active language, and `!!` language switches only last until the end of
+	/// the interpolation
+	InterpStart,
+	/// `}` ending an interpolation
+	InterpEnd,
 	/// Character literal
 	/// - `tt=3`
 	Char(char),
@@ -275,19 +384,39 @@ pub enum TokType {
 	///   See [`Op::new_self`] for specific `td` values (note the used `td` value here is one more
 	///   than used for `Op::new_self`)
 	Set(Option<Op>),
-	/// Comment token. Used exclusively by the translator to return comments
+	/// Comment token. Used exclusively by the translator to return comments. Block comments can be
+	/// nested, and the text of a block comment includes any comments nested in it
 	/// - `tt=255`
 	Comment(String, Vec<u8>),
+	/// Doc comment in the given language. This is either a `\\\` line doc comment or a
+	/// `\** ... *\` block doc comment, and holds the text after the opening backslashes or stars.
+	/// Unlike [`Comment`](Self::Comment), doc comments are kept by
+	/// [`comments_filter`](crate::comments_filter)
+	/// - `tt=255`
+	DocComment(String, Vec<u8>),
+	/// `!!` language switch to the language with the given code. The span covers the whole
+	/// directive including the terminating newline or semicolon. Can be turned off with
+	/// [`Lexer::language_switches`](crate::Lexer::language_switches)
+	LanguageSwitch(String),
+	/// Bytes skipped over when recovering from a lexing error. Only produced by
+	/// [`tokenize_recover`](crate::tokenize_recover)
+	Error(Vec<u8>),
 }
 
 #[cfg(debug_assertions)]
 impl Debug for TokType {
 	fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
 		match self {
-			Self::Int(i) => write!(f, "Int({})", i),
-			Self::Float(i) => write!(f, "Float({})", i),
+			Self::Int(i, None) => write!(f, "Int({})", i),
+			Self::Int(i, Some(k)) => write!(f, "Int({}, {:?})", i, k),
+			Self::Float(i, None) => write!(f, "Float({})", i),
+			Self::Float(i, Some(k)) => write!(f, "Float({}, {:?})", i, k),
 			Self::Bool(b) => write!(f, "Bool({})", b),
 			Self::String(i) => write!(f, "String({:?})", String::from_utf8(i.clone()).unwrap_or(format!("{:?}", i))),
+			Self::StringStart(i) => write!(f, "StringStart({:?})", String::from_utf8(i.clone()).unwrap_or(format!("{:?}", i))),
+			Self::StringPart(i) => write!(f, "StringPart({:?})", String::from_utf8(i.clone()).unwrap_or(format!("{:?}", i))),
+			Self::InterpStart => write!(f, "InterpStart"),
+			Self::InterpEnd => write!(f, "InterpEnd"),
 			Self::Char(c) => write!(f, "Char({})", c),
 			Self::Op(op) => write!(f, "Op({:?})", op),
 			Self::Cmp(cmp) => write!(f, "Cmp({:?})", cmp),
@@ -323,7 +452,10 @@ impl Debug for TokType {
 				String::from_utf8(c.clone())
 					.map(|id| format!("{}:{}", lang, id))
 					.unwrap_or(format!("{}, {:?}", lang, c))
-			)
+			),
+			Self::DocComment(lang, c) => write!(f, "DocComment({}:{:?})", lang, String::from_utf8_lossy(c)),
+			Self::LanguageSwitch(lang) => write!(f, "LanguageSwitch({})", lang),
+			Self::Error(b) => write!(f, "Error({:?})", String::from_utf8_lossy(b)),
 		}
 	}
 }
@@ -349,9 +481,14 @@ impl Default for Cmp {
 pub(crate) enum PreTokType<'a> {
 	/// Saves the matched digit and the base of the digit
 	Int(Vec<u8>, u8, &'a LanguageRaw<'a>),
-	Float(f64),
+	/// Saves the matched float
+	Float(Vec<u8>, &'a LanguageRaw<'a>),
 	Bool(bool),
 	String(Vec<u8>),
+	StringStart(Vec<u8>),
+	StringPart(Vec<u8>),
+	InterpStart,
+	InterpEnd,
 	Char(char),
 	Op(Op),
 	Cmp(Cmp),
@@ -377,6 +514,9 @@ pub(crate) enum PreTokType<'a> {
 	NewLine(NewLine),
 	Set(Option<Op>),
 	Comment(String, Vec<u8>),
+	DocComment(String, Vec<u8>),
+	LanguageSwitch(String),
+	Error(Vec<u8>),
 }
 
 /// Newline token. Can either be [explicit](NewLine::Explicit) for `;` or
@@ -468,6 +608,18 @@ pub enum PrimitiveKeyword {
 	KBool
 }
 
+impl PrimitiveKeyword {
+	/// Check if this is a number type that can be used as a suffix for an integer literal
+	pub fn is_numeric(&self) -> bool {
+		matches!(self, Self::KInt | Self::KUint | Self::KDint | Self::KUdint | Self::KFloat | Self::KBfloat)
+	}
+	
+	/// Check if this is a float type that can be used as a suffix for a float literal
+	pub fn is_float(&self) -> bool {
+		matches!(self, Self::KFloat | Self::KBfloat)
+	}
+}
+
 impl From<u8> for PrimitiveKeyword {
 	fn from(value: u8) -> Self {
 		[
@@ -480,22 +632,26 @@ impl From<u8> for PrimitiveKeyword {
 impl<'a> From<PreTokType<'a>> for TokType {
 	fn from(value: PreTokType<'a>) -> Self {
 		match value {
-			PreTokType::Int(matched, 10, l) => TokType::Int(new_biguint(matched, 10, &l.keywords.digits)),
+			PreTokType::Int(matched, 10, l) => TokType::Int(new_biguint(matched, 10, &l.keywords.digits), None),
 			PreTokType::Int(matched, base, l) => {
 				let prefixes = match &l.keywords.digits {
-					Digits::Short(t) => [t.oct_pre_u8, t.oct_pre_u8, t.oct_pre_u8, t.u8arrays[0]].map(|(_, t)| 4 - t as usize).to_vec(),
-					Digits::Long(t) => [t.oct_pre_u8, t.oct_pre_u8, t.oct_pre_u8, t.u8arrays[0]].map(|(_, t)| 4 - t as usize).to_vec(),
+					Digits::Short(t) => [t.bin_pre_u8, t.hex_pre_u8, t.oct_pre_u8, t.u8arrays[0]].map(|(_, t)| 4 - t as usize).to_vec(),
+					Digits::Long(t) => [t.bin_pre_u8, t.hex_pre_u8, t.oct_pre_u8, t.u8arrays[0]].map(|(_, t)| 4 - t as usize).to_vec(),
 				};
 				match base {
-					2 => TokType::Int(new_biguint(matched[prefixes[3] + prefixes[0]..].to_vec(), 2, &l.keywords.digits)),
-					16 => TokType::Int(new_biguint(matched[prefixes[3] + prefixes[1]..].to_vec(), 16, &l.keywords.digits)),
-					8 => TokType::Int(new_biguint(matched[prefixes[3] + prefixes[2]..].to_vec(), 8, &l.keywords.digits)),
+					2 => TokType::Int(new_biguint(matched[prefixes[3] + prefixes[0]..].to_vec(), 2, &l.keywords.digits), None),
+					16 => TokType::Int(new_biguint(matched[prefixes[3] + prefixes[1]..].to_vec(), 16, &l.keywords.digits), None),
+					8 => TokType::Int(new_biguint(matched[prefixes[3] + prefixes[2]..].to_vec(), 8, &l.keywords.digits), None),
 					_ => unreachable!()
 				}
 			}
-			PreTokType::Float(f) => TokType::Float(f),
+			PreTokType::Float(matched, l) => TokType::Float(Decimal::new(matched, &l.keywords.digits), None),
 			PreTokType::Bool(a) => TokType::Bool(a),
 			PreTokType::String(a) => TokType::String(a),
+			PreTokType::StringStart(a) => TokType::StringStart(a),
+			PreTokType::StringPart(a) => TokType::StringPart(a),
+			PreTokType::InterpStart => TokType::InterpStart,
+			PreTokType::InterpEnd => TokType::InterpEnd,
 			PreTokType::Char(a) => TokType::Char(a),
 			PreTokType::Op(a) => TokType::Op(a),
 			PreTokType::Cmp(a) => TokType::Cmp(a),
@@ -521,6 +677,9 @@ impl<'a> From<PreTokType<'a>> for TokType {
 			PreTokType::NewLine(a) => TokType::NewLine(a),
 			PreTokType::Set(a) => TokType::Set(a),
 			PreTokType::Comment(a, b) => TokType::Comment(a, b),
+			PreTokType::DocComment(a, b) => TokType::DocComment(a, b),
+			PreTokType::LanguageSwitch(a) => TokType::LanguageSwitch(a),
+			PreTokType::Error(b) => TokType::Error(b),
 		}
 	}
 }
@@ -583,6 +742,75 @@ pub enum Arrow {
 	Double
 }
 
+/// # Decimal float
+///
+/// Exact representation of a float literal as `mantissa * 10^exponent`. This is kept in base 10 so
+/// literals can be used for `bfloat` values without losing precision. Trailing zeros are removed
+/// from the mantissa so equal values have equal representations
+#[derive(Clone, PartialEq)]
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+pub struct Decimal {
+	/// Decimal digits of the float
+	pub mantissa: BigUint,
+	/// Base 10 exponent. Exponents too large for an `isize` are saturated
+	pub exponent: isize,
+}
+
+impl Decimal {
+	/// Make a new `Decimal` from a matched float and digits
+	///
+	/// The matched float is of the form `int[.frac][(e|E)[+|-]exp]` where `int`, `frac`, and `exp`
+	/// use the given digits
+	fn new(matched: Vec<u8>, d: &Digits) -> Self {
+		let (mantissa, exp) = match matched.iter().position(|b| *b == b'e' || *b == b'E') {
+			Some(p) => (&matched[..p], &matched[p + 1..]),
+			None => (&matched[..], &matched[..0]),
+		};
+		let (int, frac) = match mantissa.iter().position(|b| *b == b'.') {
+			Some(p) => (&mantissa[..p], &mantissa[p + 1..]),
+			None => (mantissa, &mantissa[..0]),
+		};
+		let (neg, exp) = match exp.first() {
+			Some(b'-') => (true, &exp[1..]),
+			Some(b'+') => (false, &exp[1..]),
+			_ => (false, exp),
+		};
+		let mut exponent = d.separate(10, exp.to_vec()).into_iter()
+			.fold(0isize, |acc, k| acc.saturating_mul(10).saturating_add(k as isize));
+		if neg { exponent = -exponent }
+		exponent = exponent.saturating_sub(d.separate(10, frac.to_vec()).len() as isize);
+		let mut mantissa = new_biguint([int, frac].concat(), 10, d);
+		if mantissa == BigUint::default() {
+			return Self { mantissa, exponent: 0 }
+		}
+		while (&mantissa % 10u8) == BigUint::default() {
+			mantissa /= 10u8;
+			exponent = exponent.saturating_add(1)
+		}
+		Self { mantissa, exponent }
+	}
+	
+	/// Get the nearest `f64` to the value
+	pub fn to_f64(&self) -> f64 {
+		format!("{}", self).parse().unwrap_or(f64::NAN)
+	}
+}
+
+impl std::fmt::Display for Decimal {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}e{}", self.mantissa, self.exponent)
+	}
+}
+
+/// Normalize matched identifier bytes to NFC so that canonically equivalent identifiers compare
+/// equal
+fn nfc(matched: Vec<u8>) -> Vec<u8> {
+	match std::str::from_utf8(&matched) {
+		Ok(s) if !is_nfc(s) => s.nfc().collect::<String>().into_bytes(),
+		_ => matched
+	}
+}
+
 /// Make a new [`BigUint`] from a matched digit, base, and digits
 fn new_biguint(matched: Vec<u8>, base: u8, d: &Digits) -> BigUint {
 	let mut out = BigUint::new(Vec::new());
@@ -619,7 +847,7 @@ impl<'a> PreTokType<'a> {
 				3 => PreTokType::Int(matcher, 2, l),
 				4 => PreTokType::Int(matcher, 16, l),
 				5 => PreTokType::Int(matcher, 8, l),
-				6 => PreTokType::Float(0.),
+				6 => PreTokType::Float(matcher, l),
 				// string and char are only ever manually constructed
 				_ => unreachable!()
 			}
@@ -640,7 +868,7 @@ impl<'a> PreTokType<'a> {
 			6 => PreTokType::ControlKeyword(td.into()),
 			7 => PreTokType::DataKeyword(td.into()),
 			8 => PreTokType::PrimitiveKeyword(td.into()),
-			9 => PreTokType::Identifier(l.name.1.to_string(), matcher),
+			9 => PreTokType::Identifier(l.name.1.to_string(), nfc(matcher)),
 			10 => match td {
 				0 => PreTokType::NewLine(NewLine::Implicit),
 				1 => PreTokType::NewLine(NewLine::Explicit),
@@ -0,0 +1,142 @@
+//! # Translation
+//!
+//! Translate lexed source from one language into another. Keywords, booleans, and number literals
+//! are spelt in the target language, brackets are flipped if the languages are written in
+//! different directions, and everything else is copied verbatim from the source
+
+use lang_inner::{Digits, LanguageRaw};
+use crate::tok::{Token, TokType};
+use crate::{get, LanguageTuple};
+
+/// # Translate a token stream
+///
+/// Re-emit the source `src` that was lexed into `toks` in the language `to`. `from` is the language
+/// the source starts in and `buf` holds any custom languages that may be switched to.
+///
+/// - [Control](TokType::ControlKeyword), [data](TokType::DataKeyword), and
+///   [primitive](TokType::PrimitiveKeyword) keywords and [booleans](TokType::Bool) use the
+///   spellings from `to`
+/// - [Integer](TokType::Int) and [float](TokType::Float) literals use the digits and base prefixes
+///   from `to`. The base, digit separators, and type suffixes are kept
+/// - Round and square brackets are flipped if `from` and `to` are written in different directions
+/// - [Language switches](TokType::LanguageSwitch) are removed since everything is written in `to`
+/// - Comments, strings, identifiers, and whitespace are kept verbatim
+///
+/// Identifiers are not checked against the keywords of `to`, so an identifier that is a keyword in
+/// `to` will be lexed as that keyword
+///
+/// `toks` must hold the [language switch](TokType::LanguageSwitch) tokens of `src`, since they are
+/// used to follow the language of the source. Tokens lexed with
+/// [`language_switches(false)`](crate::Lexer::language_switches) are translated as if the source
+/// never leaves `from`, so numbers and brackets after a `!!` switch are mistranslated and the
+/// switches themselves are copied verbatim
+pub fn translate<'a>(
+	src: &[u8],
+	toks: &[Token],
+	from: &'a LanguageRaw<'a>,
+	buf: &'a [LanguageTuple<'a>],
+	to: &LanguageRaw<'_>,
+) -> String {
+	let mut out = Vec::with_capacity(src.len());
+	let mut from = from;
+	// language active outside each open curly brace or interpolation, as in the lexer
+	let mut scopes = Vec::new();
+	let mut last = 0;
+	for tok in toks {
+		out.extend_from_slice(&src[last..tok.ps.offset]);
+		last = tok.pe.offset;
+		let text = String::from_utf8_lossy(&src[tok.ps.offset..tok.pe.offset]);
+		match &tok.tt {
+			TokType::LParenCurly | TokType::InterpStart => scopes.push(from),
+			TokType::RParenCurly | TokType::InterpEnd => if let Some(l) = scopes.pop() { from = l },
+			_ => {}
+		}
+		match &tok.tt {
+			TokType::LanguageSwitch(code) => {
+				if let Ok((l, _)) = get(code, buf) { from = l }
+				// keep the line ending so the statement before the switch is still ended
+				if let Some(&b @ (b'\n' | b';')) = text.as_bytes().last() { out.push(b) }
+			}
+			TokType::Int(_, suffix) | TokType::Float(_, suffix) => {
+				let number = match suffix {
+					Some(k) => text.strip_suffix(spelling(&TokType::PrimitiveKeyword(*k), from).unwrap_or_default()).unwrap_or(&text),
+					None => &text,
+				};
+				out.extend_from_slice(self::number(number, &from.keywords.digits, &to.keywords.digits).as_bytes());
+				if let Some(k) = suffix {
+					out.extend_from_slice(spelling(&TokType::PrimitiveKeyword(*k), to).unwrap_or_default().as_bytes())
+				}
+			}
+			TokType::LParen | TokType::RParen | TokType::LParenSquare | TokType::RParenSquare
+				if from.left_right != to.left_right => out.push(flip(text.as_bytes()[0])),
+			tt => match spelling(tt, to) {
+				Some(s) => out.extend_from_slice(s.as_bytes()),
+				None => out.extend_from_slice(text.as_bytes()),
+			}
+		}
+	}
+	out.extend_from_slice(&src[last.min(src.len())..]);
+	String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Spelling of a keyword or boolean token in a language. Returns `None` for any other token
+pub(crate) fn spelling<'a>(tt: &TokType, l: &LanguageRaw<'a>) -> Option<&'a str> {
+	let k = &l.keywords;
+	match tt {
+		TokType::ControlKeyword(t) => k.keywords.into_iter().nth(*t as usize),
+		TokType::DataKeyword(t) => k.type_kwds.into_iter().nth(*t as usize),
+		TokType::PrimitiveKeyword(t) => k.builtins.into_iter().nth(*t as usize),
+		TokType::Bool(t) => k.bool.into_iter().nth(if *t { 0 } else { 1 }),
+		_ => None
+	}
+}
+
+/// Flip a round or square bracket
+pub(crate) fn flip(b: u8) -> u8 {
+	match b {
+		b'(' => b')',
+		b')' => b'(',
+		b'[' => b']',
+		b']' => b'[',
+		b => b
+	}
+}
+
+/// Base prefixes and digits of a language
+pub(crate) fn digits(d: &Digits) -> ([char; 3], &[char]) {
+	match d {
+		Digits::Short(t) => ([t.bin_pre, t.hex_pre, t.oct_pre], &t.digits),
+		Digits::Long(t) => ([t.bin_pre, t.hex_pre, t.oct_pre], &t.digits),
+	}
+}
+
+/// Respell a number literal from one set of digits to another
+///
+/// Base prefixes and digits are replaced with the matching ones in `to`. Anything else, such as
+/// digit separators or the decimal point, is kept. Upper case hexadecimal digits become lower case
+/// if `to` has no upper case digits
+fn number(text: &str, from: &Digits, to: &Digits) -> String {
+	let ((from_pre, from_d), (to_pre, to_d)) = (digits(from), digits(to));
+	let mut out = String::with_capacity(text.len());
+	let mut chars = text.chars();
+	// only hexadecimal numbers can use digits past 9. this stops the exponent being respelt
+	let mut base = 10;
+	let mut prefixed = text.chars();
+	if let (Some(zero), Some(pre)) = (prefixed.next(), prefixed.next()) {
+		if zero == from_d[0] {
+			if let Some(i) = from_pre.iter().position(|t| *t == pre) {
+				out.push(to_d[0]);
+				out.push(to_pre[i]);
+				base = if i == 1 { from_d.len() } else { 10 };
+				chars = prefixed
+			}
+		}
+	}
+	for c in chars {
+		match from_d[..base].iter().position(|t| *t == c) {
+			Some(i) => out.push(*to_d.get(i).unwrap_or_else(|| &to_d[i - 6])),
+			None => out.push(c),
+		}
+	}
+	out
+}
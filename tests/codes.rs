@@ -1,23 +1,62 @@
+mod common;
+
 use lang;
+use lang::LookupError;
+use common::custom;
 
 #[test]
 fn correct_formatted() {
     let buf = Vec::new();
     for i in ["eN", "En", "EN"] {
-        assert!(lang::get(i, &buf).is_some());
+        assert!(lang::get(i, &buf).is_ok());
+    }
+}
+
+#[test]
+fn full_name() {
+    let buf = Vec::new();
+    for i in ["English", "english", "DEUTSCH"] {
+        assert!(lang::get(i, &buf).is_ok(), "{} not found", i);
+    }
+}
+
+#[test]
+fn custom_languages() {
+    let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+    let src = src.replacen("{ English en", "{ Custom xx cu Cust", 1);
+    let buf = vec![custom(&src)];
+    for i in ["xx", "XX", "custom", "cu", "cust"] {
+        match lang::get(i, &buf) {
+            Ok((l, _)) => assert_eq!(l.name.1, "xx"),
+            Err(e) => panic!("{}", e)
+        }
     }
 }
 
+#[test]
+fn ambiguous() {
+    let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+    let src = src.replacen("{ English en", "{ British gb en", 1);
+    let buf = vec![custom(&src)];
+    assert_eq!(lang::get("EN", &buf).map(|_| ()), Err(LookupError::Ambiguous {
+        name: "EN".to_string(),
+        codes: vec!["en".to_string(), "gb".to_string()]
+    }));
+    assert!(lang::get("gb", &buf).is_ok());
+}
+
 #[cfg(test)]
 mod incorrect_codes {
+    use lang::LookupError;
+
     #[test]
     fn too_long() {
         let buf = Vec::new();
-        assert!(lang::get("___", &buf).is_none());
+        assert_eq!(lang::get("___", &buf).map(|_| ()), Err(LookupError::NotFound("___".to_string())));
     }
     #[test]
     fn too_short() {
         let buf = Vec::new();
-        assert!(lang::get("_", &buf).is_none());
+        assert!(lang::get("_", &buf).is_err());
     }
 }
@@ -0,0 +1,37 @@
+//! Helpers shared between test files
+
+#![allow(dead_code)]
+
+use lang::LanguageTuple;
+use lang_inner::LanguageRaw;
+use lang_inner::compress::{Compress, UStream};
+use lang_inner::tables::tabularize;
+
+/// Make a custom language from fckl source
+pub fn custom(src: &str) -> LanguageTuple<'_> {
+	let l = LanguageRaw::from_text(src).unwrap();
+	let (transition, tt, td) = tabularize(&l);
+	(l, (UStream::compress(&transition), UStream::compress(&tt), UStream::compress(&td)))
+}
+
+/// Make a custom language from English with each `(from, to)` replaced once
+pub fn english(replace: &[(&str, &str)]) -> LanguageTuple<'static> {
+	let mut src = std::fs::read_to_string("languages/en.fckl").unwrap();
+	for (from, to) in replace {
+		assert!(src.contains(from), "English has no {:?} to replace", from);
+		src = src.replacen(from, to, 1);
+	}
+	custom(Box::leak(src.into_boxed_str()))
+}
+
+/// Right to left language `xx` with Arabic-Indic digits, and different `if`, `int`, and bool
+/// keywords
+pub fn right_to_left() -> LanguageTuple<'static> {
+	english(&[
+		("{ English en", "} Custom xx"),
+		("b x o 0 1 2 3 4 5 6 7 8 9 a b c d e f A B C D E F", "b x o ٠ ١ ٢ ٣ ٤ ٥ ٦ ٧ ٨ ٩ a b c d e f"),
+		(" not if else ", " not si else "),
+		("\nint uint", "\nent uint"),
+		("\ntrue false", "\nvrai faux"),
+	])
+}
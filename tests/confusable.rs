@@ -0,0 +1,75 @@
+mod common;
+
+use lang::{Confusable, ConfusableWith};
+use lang::tok::Position;
+
+fn confusables(s: &str) -> Vec<Confusable> {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let toks = lang::tokenize(s.bytes(), l, &buf, m).unwrap();
+	lang::confusables(&toks, &buf)
+}
+
+fn at(p: Position) -> (usize, usize) {
+	(p.ln, p.col)
+}
+
+#[test]
+fn none() {
+	assert!(confusables("a b a\nsize größe size").is_empty());
+}
+
+#[test]
+fn identifier() {
+	// Latin `a` then Cyrillic `а`
+	let res = confusables("a;\n\u{430}");
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].ident, "\u{430}");
+	assert_eq!(res[0].lang, "en");
+	assert_eq!(at(res[0].ps), (1, 0));
+	match &res[0].with {
+		ConfusableWith::Identifier { ps, ident } => {
+			assert_eq!(ident, "a");
+			assert_eq!(at(*ps), (0, 0));
+		}
+		w => panic!("Expected identifier, found {:?}", w),
+	}
+	assert_eq!(res[0].code(), (0, 0));
+	assert_eq!(res[0].to_string(), "w0001 at 1:0 (en): identifier \"\u{430}\" is confusable with identifier \"a\" at 0:0");
+}
+
+#[test]
+fn repeated() {
+	// every use of the confusable identifier is reported, pointing to the first different one
+	let res = confusables("\u{430} a a");
+	assert_eq!(res.iter().map(|t| at(t.ps)).collect::<Vec<_>>(), vec![(0, 3), (0, 5)]);
+	assert!(res.iter().all(|t| t.with == ConfusableWith::Identifier { ps: Position::default(), ident: "\u{430}".to_string() }));
+}
+
+#[test]
+fn keyword() {
+	// `if` with a Cyrillic `і`
+	let res = confusables("\u{456}f");
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].with, ConfusableWith::Keyword { kwd: "if".to_string(), lang: "en".to_string() });
+}
+
+#[test]
+fn other_language_keyword() {
+	// `set` is a keyword in English but not German. The same word is not a lookalike
+	assert!(confusables("!!de\nset").is_empty());
+	// `setz` is only a German keyword, but is checked against in English
+	let res = confusables("s\u{435}tz");
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].with, ConfusableWith::Keyword { kwd: "setz".to_string(), lang: "de".to_string() });
+}
+
+#[test]
+fn custom_language() {
+	let buf = vec![common::english(&[("{ English en", "{ Custom xx"), ("\nset ", "\nsett ")])];
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let toks = lang::tokenize("s\u{435}tt".bytes(), l, &buf, m).unwrap();
+	let res = lang::confusables(&toks, &buf);
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].with, ConfusableWith::Keyword { kwd: "sett".to_string(), lang: "xx".to_string() });
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use lang::LexError;
+use lang::tok::Position;
+
+macro_rules! error_test {
+	($name:ident, $input:literal, $code:expr, ($ln:literal, $col:literal)) => {
+		#[test]
+		fn $name() {
+			let buf = Vec::new();
+			let (l, m) = lang::get("en", &buf).unwrap();
+			match lang::tokenize($input.bytes(), l, &buf, m) {
+				Ok(toks) => panic!("Input should fail. Returned {:?}", toks),
+				Err(err) => {
+					println!("{}", err);
+					assert_eq!(err.code(), $code, "Incorrect error code");
+					assert_eq!((err.pos().ln, err.pos().col), ($ln, $col), "Incorrect error position");
+					assert_eq!(err.lang(), "en")
+				}
+			}
+		}
+	};
+}
+
+error_test!(unknown_language, "1\n!!xx\n", (0, 1), (1, 0));
+error_test!(unmatched_brace, "{}\n }", (0, 2), (1, 1));
+error_test!(unterminated_string, "a \"abc", (0, 3), (0, 2));
+error_test!(invalid_char, "'ab'", (0, 4), (0, 0));
+error_test!(empty_char, "'", (0, 4), (0, 0));
+error_test!(unterminated_comment, r"1 \* abc", (0, 5), (0, 2));
+error_test!(unterminated_comment_star, r"\* abc *", (0, 5), (0, 0));
+error_test!(unterminated_nested_comment, "a\n \\* b \\* c *\\", (0, 5), (1, 1));
+error_test!(unterminated_doc_comment, r"a \** doc", (0, 5), (0, 2));
+error_test!(invalid_comment, r"\a", (0, 6), (0, 0));
+error_test!(unknown_escape, r#"a "b\q""#, (0, 7), (0, 4));
+error_test!(invalid_unicode_escape, r#""\u{D800}""#, (0, 7), (0, 1));
+error_test!(unclosed_unicode_escape, r#""\u{41""#, (0, 7), (0, 1));
+error_test!(unterminated_raw_string, r##"a r#"abc"##, (0, 3), (0, 2));
+error_test!(unterminated_block, "\"\"\"\nabc\n\"\"", (0, 3), (0, 0));
+error_test!(unterminated_interpolation, "a \"b {c", (0, 3), (0, 2));
+error_test!(unterminated_later_interpolation, "\"a {b} c {d", (0, 3), (0, 0));
+error_test!(unterminated_continued_string, "a \"b {c} d", (0, 3), (0, 2));
+error_test!(unterminated_continued_block, "\"\"\"\na{b}\nc", (0, 3), (0, 0));
+error_test!(empty_interpolation, "\"a{}b\"", (0, 10), (0, 2));
+error_test!(blank_interpolation, "a \"b{ \\\\ c\n}\"", (0, 10), (0, 4));
+error_test!(emoji_ident, "a 😀", (0, 0), (0, 2));
+error_test!(joiner_in_ident, "a\u{200D}b", (0, 0), (0, 0));
+error_test!(raw_without_quote, "r#a", (0, 0), (0, 0));
+error_test!(char_unknown_escape, r"'\q'", (0, 7), (0, 1));
+
+#[test]
+fn unknown_language_code() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let err = lang::tokenize("!!xx\n".bytes(), l, &buf, m).unwrap_err();
+	assert_eq!(err, LexError::UnknownLanguage {
+		pos: Position::default(),
+		code: b"xx".to_vec(),
+		lang: "en".to_string(),
+	})
+}
+
+#[test]
+fn ambiguous_language() {
+	let buf = vec![common::english(&[("{ English en", "{ Custom xx english")])];
+	let (l, m) = lang::get("de", &buf).unwrap();
+	let err = lang::tokenize("a\n!!English\n".bytes(), l, &buf, m).unwrap_err();
+	assert_eq!(err.code(), (0, 8));
+	assert_eq!(err, LexError::AmbiguousLanguage {
+		pos: Position { ln: 1, col: 0, offset: 2, char_col: 0, utf16_col: 0 },
+		code: b"English".to_vec(),
+		codes: vec!["en".to_string(), "xx".to_string()],
+		lang: "de".to_string(),
+	})
+}
+
+#[test]
+fn localised_message() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let err = lang::tokenize("}".bytes(), l, &buf, m).unwrap_err();
+	assert_eq!(err.message(l), "e0003 placeholder")
+}
+
+mod recover {
+	use lang::LexError;
+	use lang::tok::TokType;
+	
+	#[test]
+	fn all_errors() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let (toks, errors) = lang::tokenize_recover("a }\n'ab' b\n\\x c\n!!xx\nd".bytes(), l, &buf, m);
+		let codes = errors.iter().map(LexError::code).collect::<Vec<_>>();
+		assert_eq!(codes, vec![(0, 2), (0, 4), (0, 6), (0, 1)]);
+		let idents = toks.iter().filter_map(|t| match &t.tt {
+			TokType::Identifier(_, i) => Some(i.clone()),
+			_ => None
+		}).collect::<Vec<_>>();
+		assert_eq!(idents, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]);
+		let skipped = toks.iter().filter_map(|t| match &t.tt {
+			TokType::Error(b) => Some(b.clone()),
+			_ => None
+		}).collect::<Vec<_>>();
+		assert_eq!(skipped, vec![b"}".to_vec(), b"'ab'".to_vec(), b"\\x".to_vec(), b"!!xx".to_vec()]);
+	}
+	
+	#[test]
+	fn resync_at_brace() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let (toks, errors) = lang::tokenize_recover("'ab{}".bytes(), l, &buf, m);
+		assert_eq!(errors.len(), 1);
+		let tts = toks.into_iter().map(|t| t.tt).collect::<Vec<_>>();
+		assert_eq!(tts, vec![TokType::Error(b"'ab".to_vec()), TokType::LParenCurly, TokType::RParenCurly]);
+	}
+	
+	#[test]
+	fn no_errors() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let input = "int a = 5 \\\\ comment\n{ a }";
+		let (toks, errors) = lang::tokenize_recover(input.bytes(), l, &buf, m);
+		assert!(errors.is_empty());
+		assert_eq!(Ok(toks), lang::tokenize(input.bytes(), l, &buf, m));
+	}
+	
+	#[test]
+	fn string_continues_after_escape() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let (toks, errors) = lang::tokenize_recover(r#""a\qb\u{}c" d"#.bytes(), l, &buf, m);
+		assert_eq!(errors, vec![
+			LexError::InvalidEscape { pos: lang::tok::Position { col: 2, offset: 2, char_col: 2, utf16_col: 2, ..Default::default() }, bytes: b"\\q".to_vec(), lang: "en".to_string() },
+			LexError::InvalidEscape { pos: lang::tok::Position { col: 5, offset: 5, char_col: 5, utf16_col: 5, ..Default::default() }, bytes: b"\\u{}".to_vec(), lang: "en".to_string() },
+		]);
+		assert_eq!(toks[0].tt, TokType::String(b"abc".to_vec()));
+	}
+}
@@ -0,0 +1,84 @@
+use lang_inner::{FcklError, LanguageRaw};
+
+/// Parse English with the given (0 indexed) lines replaced, and only keeping the first `keep` lines
+fn parse_with(replace: &[(usize, &str)], keep: usize) -> Result<(), FcklError> {
+	let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+	let src = src.lines().enumerate()
+		.take(keep)
+		.map(|(i, l)| replace.iter().find(|(n, _)| *n == i).map_or(l, |(_, r)| *r))
+		.collect::<Vec<_>>()
+		.join("\n");
+	LanguageRaw::from_text(&src).map(|_| ())
+}
+
+macro_rules! fckl_test {
+    ($name:ident, $replace:expr, $keep:expr, $line:literal, $section:literal, $expected:literal, $found:expr) => {
+		#[test]
+		fn $name() {
+			let err = parse_with(&$replace, $keep).unwrap_err();
+			println!("{}", err);
+			assert_eq!(err, FcklError {
+				line: $line,
+				section: $section,
+				expected: $expected.to_string(),
+				found: $found.map(|t: &str| t.to_string()),
+			})
+		}
+	};
+}
+
+#[test]
+fn valid() {
+	assert!(parse_with(&[], usize::MAX).is_ok())
+}
+
+fckl_test!(direction, [(0, "| English en")], usize::MAX, 1, "name", "'{' or '}'", Some("|"));
+fckl_test!(missing_code, [(0, "{ English")], usize::MAX, 1, "name", "language code", None);
+fckl_test!(digit_count, [(1, "b x o 0 1 2")], usize::MAX, 2, "digits", "19 or 25 digits", Some("6"));
+fckl_test!(digit_char, [(1, "b x o 01 1 2")], usize::MAX, 2, "digits", "single character", Some("01"));
+fckl_test!(
+	missing_keyword,
+	[(3, "struct properties enum variants self Self extension extend")],
+	usize::MAX, 4, "type keywords", "const", None
+);
+fckl_test!(extra_keyword, [(5, "true false maybe")], usize::MAX, 6, "bool keywords", "end of line", Some("maybe"));
+fckl_test!(missing_error, [], 12, 13, "errors", "e0005", None);
+fckl_test!(command_spaces, [(35, "new project")], usize::MAX, 36, "CLI commands", "new", Some("new project"));
+fckl_test!(short_flag, [(55, "help hh")], usize::MAX, 56, "CLI arguments", "help short flag character", Some("hh"));
+fckl_test!(truncated, [], 74, 75, "CLI arguments", "comment help", None);
+
+mod round_trip {
+	use lang_inner::LanguageRaw;
+	
+	macro_rules! round_trip_test {
+	    ($($name:ident),*$(,)?) => {$(
+			#[test]
+			fn $name() {
+				let src = std::fs::read_to_string(concat!("languages/", stringify!($name), ".fckl")).unwrap();
+				let l = LanguageRaw::from_text(&src).unwrap();
+				let out = l.to_fckl();
+				assert_eq!(out.trim_end(), src.trim_end());
+				assert_eq!(LanguageRaw::from_text(&out).unwrap().to_fckl(), out)
+			}
+		)*};
+	}
+	
+	round_trip_test!(en, de);
+	
+	#[test]
+	fn aliases() {
+		let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+		let src = src.replacen("{ English en", "{ English en eng  english", 1);
+		let l = LanguageRaw::from_text(&src).unwrap();
+		assert_eq!(l.aliases().collect::<Vec<_>>(), vec!["eng", "english"]);
+		assert!(l.to_fckl().starts_with("{ English en eng english\n"))
+	}
+	
+	#[test]
+	fn canonical() {
+		let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+		let spaced = src.replacen("true false", "true   false ", 1);
+		let l = LanguageRaw::from_text(&spaced).unwrap();
+		assert_eq!(l.to_fckl().trim_end(), src.trim_end())
+	}
+}
@@ -0,0 +1,99 @@
+use lang::IncrementalLexer;
+
+const INPUT: &str = "int a = 5\n{ !!de\n b = 12 }\nc = 'c' \\\\ comment\nd = \"str\"\n";
+
+/// Apply edits one after the other and check the tokens and errors match lexing the whole text
+fn check(edits: &[(std::ops::Range<usize>, &str)]) {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let mut lexer = IncrementalLexer::new(INPUT.as_bytes().to_vec(), l, &buf, m);
+	let mut text = INPUT.to_string();
+	for (range, replacement) in edits {
+		text.replace_range(range.clone(), replacement);
+		lexer.edit(range.clone(), replacement.as_bytes());
+		assert_eq!(lexer.text(), text.as_bytes());
+		let (toks, errors) = lang::tokenize_recover(text.bytes(), l, &buf, m);
+		assert_eq!(lexer.tokens(), &toks[..], "Incorrect tokens for {:?}", text);
+		assert_eq!(lexer.errors(), &errors[..], "Incorrect errors for {:?}", text);
+	}
+}
+
+#[test]
+fn extend_identifier() {
+	check(&[(5..5, "bc"), (4..4, "x")])
+}
+
+#[test]
+fn insert_line() {
+	check(&[(10..10, "e = 1\n"), (0..0, "\n\n")])
+}
+
+#[test]
+fn delete() {
+	check(&[(0..10, ""), (2..5, "")])
+}
+
+#[test]
+fn scopes() {
+	check(&[(10..11, ""), (10..10, "{"), (INPUT.len()..INPUT.len(), "}")])
+}
+
+#[test]
+fn language_switch() {
+	check(&[(14..16, "en"), (14..16, "xx"), (14..16, "de")])
+}
+
+#[test]
+fn errors() {
+	check(&[(37..37, "\\x "), (0..0, "} "), (0..2, "")])
+}
+
+#[test]
+fn local_edit() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let mut lexer = IncrementalLexer::new(INPUT.as_bytes().to_vec(), l, &buf, m);
+	let len = lexer.tokens().len();
+	let relexed = lexer.edit(4..5, b"abc");
+	assert!(relexed.len() < 3, "Re-lexed too many tokens: {:?}", relexed);
+	assert_eq!(lexer.tokens().len(), len);
+	assert_eq!(lexer.tokens()[len - 1].ps, lang::tokenize_recover(lexer.text().iter().copied(), l, &buf, m).0[len - 1].ps);
+}
+
+#[test]
+fn many_edits() {
+	// simple LCG so the edits are the same on every run
+	let mut seed = 12345u64;
+	let mut next = |n: usize| {
+		seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+		(seed >> 33) as usize % n
+	};
+	let pieces = ["a", " ", "\n", "{", "}", "12", "!!de\n", "!!en;", "\"s\"", "'c'", "r#\"s\"#", "\"{a}", "}\"", "\"\"\"\n", "\\\\ c\n", "+", "=", "if"];
+	let mut edits = Vec::new();
+	let mut len = INPUT.len();
+	for _ in 0..200 {
+		let start = next(len + 1);
+		let end = start + next((len - start).min(4) + 1);
+		let replacement = pieces[next(pieces.len())];
+		len = len - (end - start) + replacement.len();
+		edits.push((start..end, replacement));
+	}
+	check(&edits)
+}
+
+#[test]
+fn split_characters() {
+	// edits that split a multi-byte character leave invalid UTF-8 behind
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let edits: [(std::ops::Range<usize>, &[u8]); 5] = [(2..2, b"\n"), (1..2, "é".as_bytes()), (2..3, b""), (4..4, "😀".as_bytes()), (5..7, b"'")];
+	let mut text = b"'\xA9 a".to_vec();
+	let mut lexer = IncrementalLexer::new(text.clone(), l, &buf, m);
+	for (range, replacement) in edits {
+		text.splice(range.clone(), replacement.iter().copied());
+		lexer.edit(range, replacement);
+		let (toks, errors) = lang::tokenize_recover(text.iter().copied(), l, &buf, m);
+		assert_eq!(lexer.tokens(), &toks[..], "Incorrect tokens for {:?}", text);
+		assert_eq!(lexer.errors(), &errors[..], "Incorrect errors for {:?}", text);
+	}
+}
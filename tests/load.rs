@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+use lang::load::{load_dir, LoadError};
+use lang::tok::TokType;
+
+/// Make an empty directory in the system temp directory for a test
+fn test_dir(name: &str) -> PathBuf {
+	let dir = std::env::temp_dir().join(format!("fck-lang-load-{}-{}", name, std::process::id()));
+	let _ = std::fs::remove_dir_all(&dir);
+	std::fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+/// English with a different name line
+fn custom(name: &str) -> String {
+	let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+	src.replacen("{ English en", name, 1)
+}
+
+#[test]
+fn load() {
+	let dir = test_dir("load");
+	std::fs::write(dir.join("xx.fckl"), custom("{ Custom xx")).unwrap();
+	std::fs::write(dir.join("notes.txt"), "not a language").unwrap();
+	let (langs, errors) = load_dir(&dir);
+	assert!(errors.is_empty(), "{:?}", errors);
+	assert_eq!(langs.len(), 1);
+	let (l, (m1, m2, m3)) = &langs[0];
+	assert_eq!(l.name, ("Custom", "xx"));
+	let toks = lang::tokenize("if 12".bytes(), l, &langs, (m1, m2, m3)).unwrap();
+	assert_eq!(toks.len(), 2);
+	assert_eq!(toks[1], TokType::Int(12u8.into(), None));
+	let (en, m) = lang::get("en", &langs).unwrap();
+	assert!(lang::tokenize("!!xx\nif 12".bytes(), en, &langs, m).is_ok());
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn errors() {
+	let dir = test_dir("errors");
+	std::fs::write(dir.join("a.fckl"), custom("{ Custom")).unwrap();
+	std::fs::write(dir.join("b.fckl"), custom("{ Custom x!")).unwrap();
+	std::fs::write(dir.join("c.fckl"), custom("{ Custom xx")).unwrap();
+	let (langs, errors) = load_dir(&dir);
+	assert_eq!(langs.len(), 1);
+	assert_eq!(errors.len(), 2);
+	assert!(matches!(&errors[0], LoadError::Parse { path, error } if path.ends_with("a.fckl") && error.line == 1));
+	assert!(matches!(&errors[1], LoadError::Verify { path, findings } if path.ends_with("b.fckl") && findings.len() == 1));
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_dir() {
+	let (langs, errors) = load_dir(&std::env::temp_dir().join("fck-lang-load-missing"));
+	assert!(langs.is_empty());
+	assert!(errors.is_empty());
+}
+
+#[test]
+fn pack() {
+	use lang_inner::compress::{Compress, UStream, pack::LanguagePack};
+	let dir = test_dir("pack");
+	let src = custom("{ Packed pk");
+	let l = lang_inner::LanguageRaw::from_text(&src).unwrap();
+	let (transition, tt, td) = lang_inner::tables::tabularize(&l);
+	let pack = LanguagePack::new(&l, (UStream::compress(&transition), UStream::compress(&tt), UStream::compress(&td)));
+	std::fs::write(dir.join("pk.fckp"), pack.to_bytes()).unwrap();
+	std::fs::write(dir.join("bad.fckp"), b"fckp").unwrap();
+	let (langs, errors) = load_dir(&dir);
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(&errors[0], LoadError::Pack { path, .. } if path.ends_with("bad.fckp")));
+	assert_eq!(langs.len(), 1);
+	let (l, m) = lang::get("pk", &langs).unwrap();
+	assert_eq!(lang::tokenize("if 12".bytes(), l, &langs, m).unwrap().len(), 2);
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_pack_tables() {
+	use lang_inner::compress::{Compress, UStream, pack::{LanguagePack, PackError}};
+	let dir = test_dir("invalid_pack_tables");
+	let src = custom("{ Packed pk");
+	let l = lang_inner::LanguageRaw::from_text(&src).unwrap();
+	let (transition, tt, td) = lang_inner::tables::tabularize(&l);
+	let pack = || LanguagePack::new(&l, (UStream::compress(&transition), UStream::compress(&tt), UStream::compress(&td)));
+	// transition to a row past the end of the table. `to_bytes` writes a matching checksum
+	let mut bad_row = pack();
+	let rows = bad_row.tables.0.offsets.len();
+	*bad_row.tables.0.stream.iter_mut().find(|t| **t != 0).unwrap() = rows as u16;
+	assert_eq!(LanguagePack::from_bytes(&bad_row.to_bytes()).err(), Some(PackError::Tables));
+	// tt table with a row missing
+	let mut bad_count = pack();
+	bad_count.tables.1.offsets.pop();
+	assert_eq!(LanguagePack::from_bytes(&bad_count.to_bytes()).err(), Some(PackError::Tables));
+	std::fs::write(dir.join("pk.fckp"), bad_row.to_bytes()).unwrap();
+	let (langs, errors) = load_dir(&dir);
+	assert!(langs.is_empty());
+	assert!(matches!(&errors[..], [LoadError::Pack { error: PackError::Tables, .. }]));
+	std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,88 @@
+use lang::{Lexer, LosslessToken, Trivia, TriviaKind};
+use lang::tok::TokType;
+
+fn round_trip(input: &str) -> Vec<LosslessToken> {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let toks = lang::tokenize_lossless(input.bytes(), l, &buf, m).unwrap();
+	let out = toks.iter().flat_map(LosslessToken::full_text).collect::<Vec<_>>();
+	assert_eq!(String::from_utf8_lossy(&out), input);
+	// the tokens are the same as the normal token stream without comments or language switches
+	let expected = lang::tokenize(input.bytes(), l, &buf, m).unwrap()
+		.into_iter().filter(lang::comments_filter).filter(lang::language_switch_filter).collect::<Vec<_>>();
+	assert_eq!(toks.iter().filter_map(|t| t.token.clone()).collect::<Vec<_>>(), expected);
+	toks
+}
+
+#[test]
+fn samples() {
+	round_trip(include_str!("sample scripts/test1.fck"));
+	round_trip(include_str!("sample scripts/test2.fck"));
+	round_trip(include_str!("sample scripts/test3.fck"));
+}
+
+#[test]
+fn trivia() {
+	let toks = round_trip("  a \t= 5 \\\\ five\n\t!!de\n\\* block *\\ b;!!en;c");
+	let trivia = |kind, text: &str| Trivia { kind, text: text.as_bytes().to_vec() };
+	assert_eq!(toks[0].leading, vec![trivia(TriviaKind::Whitespace, "  ")]);
+	assert_eq!(toks[0].trailing, vec![trivia(TriviaKind::Whitespace, " \t")]);
+	assert_eq!(toks[2].text, b"5");
+	assert_eq!(toks[2].trailing, vec![
+		trivia(TriviaKind::Whitespace, " "),
+		trivia(TriviaKind::Comment("en".to_string()), "\\\\ five\n"),
+		trivia(TriviaKind::Whitespace, "\t"),
+		trivia(TriviaKind::LanguageSwitch("de".to_string()), "!!de\n"),
+		trivia(TriviaKind::Comment("de".to_string()), "\\* block *\\"),
+		trivia(TriviaKind::Whitespace, " "),
+	]);
+	assert_eq!(toks[3].token.as_ref().unwrap().tt, TokType::Identifier("de".to_string(), b"b".to_vec()));
+	// trivia after a newline is leading trivia of the next token
+	assert!(toks[4].trailing.is_empty());
+	assert_eq!(toks[5].leading, vec![trivia(TriviaKind::LanguageSwitch("en".to_string()), "!!en;")]);
+	assert!(toks.last().unwrap().token.is_none());
+}
+
+#[test]
+fn strings() {
+	round_trip("a = r#\"x\\\"#  \"\"\"\n  b\n  \"\"\" \\\\ c\n'\\n' \"a {b} c\"");
+}
+
+#[test]
+fn doc_comments() {
+	let toks = round_trip("\\\\\\ doc\na \\** block *\\ \\\\ c");
+	assert_eq!(toks[0].text, b"\\\\\\ doc\n");
+	assert_eq!(toks[2].text, b"\\** block *\\");
+	assert_eq!(toks[2].trailing.len(), 2);
+}
+
+#[test]
+fn only_trivia() {
+	let toks = round_trip(" \\\\ comment");
+	assert_eq!(toks.len(), 1);
+	assert!(toks[0].token.is_none());
+	assert_eq!(toks[0].leading.len(), 2);
+}
+
+#[test]
+fn recover() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let input = "a }\n'ab' b\n\\x c\n!!xx\nd";
+	let toks = Lexer::new(input.bytes(), l, &buf, m).recover(true).lossless()
+		.filter_map(Result::ok)
+		.flat_map(|t| t.full_text())
+		.collect::<Vec<_>>();
+	assert_eq!(toks, input.as_bytes());
+}
+
+#[test]
+fn without_switch_tokens() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let input = "a\n!!de\nb;!!en;c";
+	let with = lang::tokenize_lossless(input.bytes(), l, &buf, m).unwrap();
+	let without = Lexer::new(input.bytes(), l, &buf, m).language_switches(false).lossless()
+		.collect::<Result<Vec<_>, _>>().unwrap();
+	assert_eq!(with, without);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use lang::LanguageTuple;
+use lang::tok::{ControlKeyword, NewLine, Op, Position, Token, TokType};
+
+fn tokenize(s: &str, l: &str, buf: &[LanguageTuple<'static>]) -> Vec<Token> {
+	let (l, m) = lang::get(l, buf).unwrap();
+	lang::tokenize(s.bytes(), l, buf, m).unwrap()
+}
+
+fn render(s: &str, from: &str, to: &str, buf: &[LanguageTuple<'static>]) -> String {
+	let (to, _) = lang::get(to, buf).unwrap();
+	lang::render(&tokenize(s, from, buf), to)
+}
+
+#[test]
+fn layout() {
+	let s = "set a = 1\n\nif a  {\n    b(a[0], \"x\")\n}";
+	assert_eq!(render(s, "en", "en", &[]), s);
+	assert_eq!(render("set a=1", "en", "de", &[]), "setz a=1");
+}
+
+#[test]
+fn round_trip() {
+	let s = concat!(
+		"set a = 0x1f + 0b11 * 1_000uint ** 2.50 - 1e-9 + 1e12 + .5 + 3.0bfloat\n",
+		"b += \"x\\n\\\"{a}\\{\" + \"{\"{a}\"}\" + 'c' + '\\''\n",
+		"\\\\ line comment\n",
+		"c(true, false)[a] -> d => e \\* block\ncomment *\\ f;\n",
+		"\\** doc *\\ g != h <= i >= j == k < l > m ? n: o.p @q\n",
+	);
+	for l in ["en", "de"] {
+		let toks = tokenize(s, "en", &[]);
+		let rendered = lang::render(&toks, lang::get(l, &[]).unwrap().0);
+		let tt = |t: Vec<Token>| t.into_iter().map(|t| t.tt).collect::<Vec<_>>();
+		let mut relexed = tt(tokenize(&rendered, l, &[]));
+		for t in relexed.iter_mut() {
+			// identifiers and comments hold the language they were lexed in
+			if let TokType::Identifier(lang, _) | TokType::Comment(lang, _) | TokType::DocComment(lang, _) = t {
+				*lang = "en".to_string()
+			}
+		}
+		assert_eq!(relexed, tt(toks), "Rendered: {}", rendered);
+	}
+}
+
+#[test]
+fn comments() {
+	// block comment delimiters in a line comment at the end of the input
+	assert_eq!(render("a \\\\ x *\\ y", "en", "en", &[]), "a \\\\ x *\\ y\n");
+	assert_eq!(render("a \\\\\\ x \\* y", "en", "en", &[]), "a \\\\\\ x \\* y\n");
+	assert_eq!(render("a \\\\ x\\", "en", "en", &[]), "a \\\\ x\\\n");
+	assert_eq!(render("a \\\\ x", "en", "en", &[]), "a \\* x*\\");
+	assert_eq!(render("a \\* x \\* y *\\ *\\ b", "en", "en", &[]), "a \\* x \\* y *\\ *\\ b");
+}
+
+#[test]
+fn numbers() {
+	assert_eq!(render("0x1F 1_000int 2.50 1e-9 1e12 0.000001", "en", "en", &[]), "31 1000int 2.5 1.0e-9 1.0e12 0.000001");
+}
+
+#[test]
+fn generated() {
+	let toks = [
+		TokType::ControlKeyword(ControlKeyword::KIf),
+		TokType::Identifier("en".to_string(), b"a".to_vec()),
+		TokType::Op(Op::Minus),
+		TokType::Op(Op::Minus),
+		TokType::Int(1u8.into(), None),
+		TokType::NewLine(NewLine::Implicit),
+		TokType::Identifier("en".to_string(), b"b".to_vec()),
+	].map(|tt| Token { ps: Position::default(), pe: Position::default(), tt });
+	let buf = Vec::new();
+	assert_eq!(lang::render(&toks, lang::get("en", &buf).unwrap().0), "if a- -1\nb");
+}
+
+#[test]
+fn language_switches() {
+	let tt = |t: Vec<Token>| t.into_iter().map(|t| t.tt).collect::<Vec<_>>();
+	for (s, expected) in [("!!de\n'c'", "'c'"), ("a\n!!de\nsetz 1", "a\nset 1"), ("a;!!de;setz 1", "a;set 1")] {
+		let toks = tokenize(s, "en", &[]);
+		let rendered = lang::render(&toks, lang::get("en", &[]).unwrap().0);
+		assert_eq!(rendered, expected);
+		let without_switches = tt(toks).into_iter().filter(|t| !matches!(t, TokType::LanguageSwitch(_))).collect::<Vec<_>>();
+		assert_eq!(tt(tokenize(&rendered, "en", &[])), without_switches);
+	}
+}
+
+#[test]
+fn adjacent_strings() {
+	// `""` then `"q"` would be written as a block string opener
+	let toks = tokenize("\"\"r\"q\"", "en", &[]);
+	let rendered = lang::render(&toks, lang::get("en", &[]).unwrap().0);
+	assert_eq!(rendered, "\"\" \"q\"");
+	let tt = |t: Vec<Token>| t.into_iter().map(|t| t.tt).collect::<Vec<_>>();
+	assert_eq!(tt(tokenize(&rendered, "en", &[])), tt(toks));
+}
+
+#[test]
+fn raw_prefix() {
+	// an `r` identifier directly before a string is not a raw string prefix
+	let toks = [
+		TokType::Identifier("en".to_string(), b"r".to_vec()),
+		TokType::String(b"x".to_vec()),
+		TokType::Identifier("en".to_string(), b"br".to_vec()),
+		TokType::String(b"y".to_vec()),
+	].map(|tt| Token { ps: Position::default(), pe: Position::default(), tt });
+	let buf = Vec::new();
+	let rendered = lang::render(&toks, lang::get("en", &buf).unwrap().0);
+	assert_eq!(rendered, "r \"x\"br\"y\"");
+	assert_eq!(tokenize(&rendered, "en", &buf).into_iter().map(|t| t.tt).collect::<Vec<_>>(), toks.map(|t| t.tt));
+}
+
+#[test]
+fn right_to_left() {
+	let buf = vec![common::right_to_left()];
+	assert_eq!(render("if f(a[10]) {}", "en", "xx", &buf), "si f)a]١٠[( {}");
+	assert_eq!(render("si f)a]١٠[( {}", "xx", "en", &buf), "if f(a[10]) {}");
+}
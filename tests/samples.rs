@@ -3,27 +3,32 @@ use num_bigint::BigUint;
 
 macro_rules! wrap_pos {
     (($ps1:expr, $ps2:expr), ($pe1:expr, $pe2:expr), $ty:expr) => { Token {
-		ps: Position { ln: $ps1, col: $ps2 },
-		pe: Position { ln: $pe1, col: $pe2 },
+		ps: Position { ln: $ps1, col: $ps2, ..Position::default() },
+		pe: Position { ln: $pe1, col: $pe2, ..Position::default() },
 		tt: $ty
 	} };
 }
 
 macro_rules! nl {
     ($ps1:expr, $ps2:expr) => { Token {
-		ps: Position { ln: $ps1, col: $ps2 },
-		pe: Position { ln: $ps1 + 1, col: 0 },
+		ps: Position { ln: $ps1, col: $ps2, ..Position::default() },
+		pe: Position { ln: $ps1 + 1, col: 0, ..Position::default() },
 		tt: TokType::NewLine(NewLine::Implicit)
 	} };
     (e, $ps1:expr, $ps2:expr) => { Token {
-		ps: Position { ln: $ps1, col: $ps2 },
-		pe: Position { ln: $ps1 + 1, col: 0 },
+		ps: Position { ln: $ps1, col: $ps2, ..Position::default() },
+		pe: Position { ln: $ps1 + 1, col: 0, ..Position::default() },
 		tt: TokType::NewLine(NewLine::Explicit)
 	} };
     () => { TokType::NewLine(NewLine::Implicit) };
     (e) => { TokType::NewLine(NewLine::Explicit) };
 }
 
+macro_rules! switch {
+	(($ps1:expr, $ps2:expr), ($pe1:expr, $pe2:expr), $lang:ident) => { wrap_pos!(($ps1, $ps2), ($pe1, $pe2), switch!($lang)) };
+	($lang:ident) => { TokType::LanguageSwitch(stringify!($lang).to_string()) };
+}
+
 macro_rules! value {
 	(($ps1:expr, $ps2:expr), ($pe1:expr, $pe2:expr), $ty:tt) => { wrap_pos!(($ps1, $ps2), ($pe1, $pe2), value!($ty)) };
     [true] => { TokType::Bool(true) };
@@ -61,7 +66,7 @@ macro_rules! int {
 	(($ps1:expr, $ps2:expr), ($pe1:expr, $pe2:expr), $value:literal) => { wrap_pos!(($ps1, $ps2), ($pe1, $pe2), int!($value)) };
 	(($ps1:expr, $ps2:expr), ($pe1:expr, $pe2:expr), $value:literal, $base:literal) => { wrap_pos!(($ps1, $ps2), ($pe1, $pe2), int!($value, $base)) };
     ($value:literal) => { int!($value, 10) };
-    ($value:literal, $base:literal) => { TokType::Int(BigUint::parse_bytes(stringify!($value).as_bytes(), $base).unwrap()) };
+    ($value:literal, $base:literal) => { TokType::Int(BigUint::parse_bytes(stringify!($value).as_bytes(), $base).unwrap(), None) };
 }
 
 macro_rules! kwd {
@@ -111,10 +116,38 @@ macro_rules! ident {
     ($lang:ident : $ident:ident) => { TokType::Identifier(stringify!($lang).to_string(), stringify!($ident).as_bytes().to_vec()) };
 }
 
+/// Expected token. Positions are only checked by line and column
+trait Expected: std::fmt::Debug {
+	fn matches(&self, tok: &Token) -> bool;
+}
+
+impl Expected for Token {
+	fn matches(&self, tok: &Token) -> bool {
+		(self.ps.ln, self.ps.col, self.pe.ln, self.pe.col) == (tok.ps.ln, tok.ps.col, tok.pe.ln, tok.pe.col)
+			&& self.tt == tok.tt
+	}
+}
+
+impl Expected for TokType {
+	fn matches(&self, tok: &Token) -> bool {
+		tok == self
+	}
+}
+
+/// Check a position agrees with the input
+fn check_position(input: &str, p: Position) {
+	let before = &input[..p.offset];
+	let line = &before[before.rfind('\n').map_or(0, |t| t + 1)..];
+	assert_eq!(before.matches('\n').count(), p.ln, "Incorrect line for {:?}", p);
+	assert_eq!(line.len(), p.col, "Incorrect column for {:?}", p);
+	assert_eq!(line.chars().count(), p.char_col, "Incorrect character column for {:?}", p);
+	assert_eq!(line.encode_utf16().count(), p.utf16_col, "Incorrect UTF-16 column for {:?}", p);
+}
+
 macro_rules! test_input {
     ($path:ident, $($expected:expr),*$(,)?) => {
 		#[test]
-		fn $path() -> Result<(), u16> {
+		fn $path() -> Result<(), lang::LexError> {
 			let input = include_str!(concat!("sample scripts/", stringify!($path), ".fck"));
 			let expected = vec![$($expected),*];
 			let b = input.bytes();
@@ -136,8 +169,10 @@ macro_rules! test_input {
 					for i in expected.iter() { println!("- {:?}", i); }
 					println!("\n{:^70}    {:^70}", "Returned", "Expected");
 					for (i, l) in toks.iter().zip(expected) {
-						println!("{:<70} {}= {:?}", format!("{:?}", *i), if *i == l { '=' } else { '!' }, l);
-						assert_eq!(*i, l)
+						println!("{:<70} {}= {:?}", format!("{:?}", *i), if l.matches(i) { '=' } else { '!' }, l);
+						assert!(l.matches(i), "Incorrect token {:?}, expected {:?}", i, l);
+						check_position(input, i.ps);
+						check_position(input, i.pe);
 					}
 					Ok(())
 				}
@@ -149,12 +184,13 @@ macro_rules! test_input {
 
 test_input!(
 	test1,
+	switch!((0, 0), (1, 0), en),
 	kwd!((1, 0), (1, 3), Set), ident!((1, 4), (1, 10), my_var), value!((1, 11), (1, 12), =), int!((1, 13), (1, 14), 5), nl!(1, 14),
 	kwd!((2, 0), (2, 6), Struct), ident!((2, 7), (2, 17), SomeStruct), wrap_pos!((2, 18), (2, 19), TokType::LParenCurly), nl!(2, 19),
 		kwd!((3, 4), (3, 14), Properties), wrap_pos!((3, 15), (3, 16), TokType::LParenCurly),
 			kwd!((3, 17), (3, 20), Int), ident!((3, 21), (3, 26), inner),
 		wrap_pos!((3, 27), (3, 28), TokType::RParenCurly), nl!(3, 28),
-		wrap_pos!((4, 4), (4, 5), TokType::LParenCurly),
+		wrap_pos!((4, 4), (4, 5), TokType::LParenCurly), switch!((4, 5), (5, 0), de),
 			kwd!((5, 8), (5, 10), Fn), ident!((5, 11), (5, 17), de:set_de), wrap_pos!((5, 17), (5, 18), TokType::LParen), wrap_pos!((5, 18), (5, 19), TokType::RParen), wrap_pos!((5, 20), (5, 21), TokType::LParenCurly), wrap_pos!((5, 22), (5, 23), TokType::RParenCurly), nl!(5, 23),
 		wrap_pos!((6, 4), (6, 5), TokType::RParenCurly), nl!(6, 5),
 		kwd!((7, 4), (7, 6), Fn), ident!((7, 7), (7, 11), set_), wrap_pos!((7, 11), (7, 12), TokType::LParen), kwd!((7, 12), (7, 15), Int), ident!((7, 16), (7, 21), inner), wrap_pos!((7, 21), (7, 22), TokType::RParen), value!((7, 23), (7, 25), ->), kwd!((7, 26), (7, 30), SSelf), wrap_pos!((7, 31), (7, 32), TokType::LParenCurly), nl!(7, 32),
@@ -165,11 +201,13 @@ test_input!(
 
 test_input!(
 	test2,
-	kwd!(Set), nl!(), TokType::LParenCurly, kwd!(Set), nl!(), kwd!(Set), nl!(), TokType::RParenCurly, nl!(), kwd!(Set), nl!(), kwd!(Set), nl!(), nl!()
+	switch!(en), kwd!(Set), nl!(), TokType::LParenCurly, switch!(de), kwd!(Set), nl!(), switch!(en), kwd!(Set), nl!(),
+	TokType::RParenCurly, nl!(), kwd!(Set), nl!(), switch!(de), kwd!(Set), nl!(), nl!()
 );
 
 test_input!(
 	test3,
+	switch!((0, 0), (1, 0), en),
 	int!((1, 0), (1, 10), 0123456789), nl!(1, 10),
 	int!((2, 0), (2, 4), 01, 2), nl!(2, 4),
 	int!((3, 0), (3, 10), 01234567, 8), nl!(3, 10),
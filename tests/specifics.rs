@@ -38,9 +38,9 @@ mod operators {
 			Ok(mut res) => {
 				assert_eq!(res.len(), 3, "Returned wrong number of tokens");
 				assert_eq!(res, vec![
-					Token { ps: Position { ln: 0, col: 0 }, pe: Position { ln: 1, col: 0 }, tt: TokType::NewLine(NewLine::Implicit) },
-					Token { ps: Position { ln: 1, col: 0 }, pe: Position { ln: 1, col: 1 }, tt: TokType::NewLine(NewLine::Explicit) },
-					Token { ps: Position { ln: 1, col: 1 }, pe: Position { ln: 2, col: 0 }, tt: TokType::NewLine(NewLine::Implicit) }
+					Token { ps: Position::default(), pe: Position { ln: 1, col: 0, offset: 1, char_col: 0, utf16_col: 0 }, tt: TokType::NewLine(NewLine::Implicit) },
+					Token { ps: Position { ln: 1, col: 0, offset: 1, char_col: 0, utf16_col: 0 }, pe: Position { ln: 1, col: 1, offset: 2, char_col: 1, utf16_col: 1 }, tt: TokType::NewLine(NewLine::Explicit) },
+					Token { ps: Position { ln: 1, col: 1, offset: 2, char_col: 1, utf16_col: 1 }, pe: Position { ln: 2, col: 0, offset: 3, char_col: 0, utf16_col: 0 }, tt: TokType::NewLine(NewLine::Implicit) }
 				])
 			}
 			Err(err) => assert!(false, "Failed parsing: {}", err)
@@ -75,9 +75,9 @@ mod digits {
 	macro_rules! digit_test {
 	    ($name:ident, $test:literal, $($v:literal),*$(,)?) => {
 			#[test]
-			fn $name() -> Result<(), u16> {
+			fn $name() -> Result<(), lang::LexError> {
 				let test = $test;
-				let expected = vec![$(TokType::Int(($v as u16).into())),*];
+				let expected = vec![$(TokType::Int(($v as u16).into(), None)),*];
 				let buf = Vec::new();
 				let (l, (transition, tt, td)) = lang::get("en", &buf).unwrap();
 				match lang::tokenize(
@@ -105,6 +105,249 @@ mod digits {
 		"0x0 0x1 0x2 0x3 0x4 0x5 0x6 0x7 0x8 0x9 0xa 0xb 0xc 0xd 0xe 0xf 0xA 0xB 0xC 0xD 0xE 0xF 0xA0b 0x0f3E",
 		0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 10, 11, 12, 13, 14, 15, 0xA0b, 0x0f3E
 	);
+	digit_test!(separators, "1_000 0xFF_FF 0b1_0 0o7_7 0_1", 1000, 0xFFFF, 2, 0o77, 1);
+	
+	#[test]
+	fn misplaced_separators() {
+		let ident = |t: &str| TokType::Identifier("en".to_string(), t.as_bytes().to_vec());
+		let int = |t: u8| TokType::Int(t.into(), None);
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		// a separator has to be between two digits, otherwise it starts an identifier
+		for (test, expected) in [
+			("_1", vec![ident("_1")]),
+			("3_", vec![int(3), ident("_")]),
+			("1__2", vec![int(1), ident("__2")]),
+			("1_foo", vec![int(1), ident("_foo")]),
+			("0x_1", vec![int(0), ident("x_1")]),
+			("0xF_", vec![int(15), ident("_")]),
+			("0b1__0", vec![int(1), ident("__0")]),
+		] {
+			let toks = lang::tokenize(test.bytes(), l, &buf, m).unwrap();
+			assert_eq!(toks.into_iter().map(|t| t.tt).collect::<Vec<_>>(), expected, "{}", test);
+		}
+	}
+	
+	/// Same as `digit_test` but uses English with the digits line replaced
+	macro_rules! multi_byte_test {
+	    ($name:ident, $digits:literal, $test:literal, $($v:literal),*$(,)?) => {
+			#[test]
+			fn $name() -> Result<(), lang::LexError> {
+				let test = $test;
+				let expected = vec![$(TokType::Int(($v as u16).into(), None)),*];
+				let buf = Vec::new();
+				let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+				let src = src.lines().enumerate()
+					.map(|(i, l)| if i == 1 { $digits } else { l })
+					.collect::<Vec<_>>()
+					.join("\n");
+				let l = LanguageRaw::from_text(&*src).unwrap();
+				assert!(!l.is_invalid(), "Language failed verification");
+				let (transition, tt, td) = lang_inner::tables::tabularize(&l);
+				match lang::tokenize(test.bytes(), &l, &buf, (&transition, &tt, &td)) {
+					Ok(toks) => {
+						for t in toks.iter() { println!("{:?}", t) }
+						assert_eq!(toks.len(), expected.len(), "Incorrect number of tokens returned");
+						for (tok, tt) in toks.iter().zip(expected.iter()) {
+							assert_eq!(tok, tt)
+						}
+						Ok(())
+					}
+					Err(e) => Err(e),
+				}
+			}
+		};
+	}
+	
+	multi_byte_test!(devanagari,
+		"b x o ० १ २ ३ ४ ५ ६ ७ ८ ९ क ख ग घ ङ च",
+		"० ९ ५१३ ०८३९ ०b१०१ ०o१७ ०xकच०",
+		0, 9, 513, 839, 0b101, 0o17, 0xAF0
+	);
+	multi_byte_test!(devanagari_separators,
+		"b x o ० १ २ ३ ४ ५ ६ ७ ८ ९ क ख ग घ ङ च",
+		"५_१३ ०xक_च",
+		513, 0xAF
+	);
+	
+	#[test]
+	fn devanagari_misplaced_separators() {
+		let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+		let src = src.lines().enumerate()
+			.map(|(i, l)| if i == 1 { "b x o ० १ २ ३ ४ ५ ६ ७ ८ ९ क ख ग घ ङ च" } else { l })
+			.collect::<Vec<_>>()
+			.join("\n");
+		let l = LanguageRaw::from_text(&src).unwrap();
+		let (transition, tt, td) = lang_inner::tables::tabularize(&l);
+		let toks = lang::tokenize("५__१ ०xच_".bytes(), &l, &[], (&transition, &tt, &td)).unwrap();
+		assert_eq!(toks.into_iter().map(|t| t.tt).collect::<Vec<_>>(), vec![
+			TokType::Int(5u8.into(), None),
+			TokType::Identifier("en".to_string(), "__१".as_bytes().to_vec()),
+			TokType::Int(0xFu8.into(), None),
+			TokType::Identifier("en".to_string(), b"_".to_vec()),
+		]);
+	}
+	multi_byte_test!(full_width,
+		"ｂ ｘ ｏ ０ １ ２ ３ ４ ５ ６ ７ ８ ９ ａ ｂ ｃ ｄ ｅ ｆ Ａ Ｂ Ｃ Ｄ Ｅ Ｆ",
+		"０ ４２ ０ｂ１１ ０ｏ７７ ０ｘｆＦ ０ｘ１ａＢ",
+		0, 42, 0b11, 0o77, 0xff, 0x1ab
+	);
+	
+	#[test]
+	fn suffixes() {
+		use lang::tok::{Decimal, PrimitiveKeyword};
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize("10int 0xFFuint 1_0udint 5float 1.5bfloat 2 int 1.5int".bytes(), l, &buf, m).unwrap();
+		let float = |k| TokType::Float(Decimal { mantissa: 15u8.into(), exponent: -1 }, k);
+		assert_eq!(res, vec![
+			TokType::Int(10u8.into(), Some(PrimitiveKeyword::KInt)),
+			TokType::Int(255u8.into(), Some(PrimitiveKeyword::KUint)),
+			TokType::Int(10u8.into(), Some(PrimitiveKeyword::KUdint)),
+			TokType::Int(5u8.into(), Some(PrimitiveKeyword::KFloat)),
+			float(Some(PrimitiveKeyword::KBfloat)),
+			TokType::Int(2u8.into(), None),
+			TokType::PrimitiveKeyword(PrimitiveKeyword::KInt),
+			float(None),
+			TokType::PrimitiveKeyword(PrimitiveKeyword::KInt),
+		]);
+		assert_eq!((res[0].ps.col, res[0].pe.col), (0, 5));
+	}
+	
+	#[test]
+	fn localised_suffixes() {
+		use lang::tok::PrimitiveKeyword;
+		let buf = Vec::new();
+		let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+		let src = src.replacen("int uint dint udint float bfloat", "ganz nat dganz dnat komma bkomma", 1);
+		let l = LanguageRaw::from_text(&*src).unwrap();
+		let (transition, tt, td) = lang_inner::tables::tabularize(&l);
+		let res = lang::tokenize("10nat 10int".bytes(), &l, &buf, (&transition, &tt, &td)).unwrap();
+		assert_eq!(res, vec![
+			TokType::Int(10u8.into(), Some(PrimitiveKeyword::KUint)),
+			TokType::Int(10u8.into(), None),
+			TokType::Identifier("en".to_string(), b"int".to_vec()),
+		]);
+	}
+	
+	#[test]
+	fn multi_byte_ident() {
+		let buf = Vec::new();
+		let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+		let src = src.replacen("b x o 0 1 2 3 4 5 6 7 8 9 a b c d e f A B C D E F", "b x o ० १ २ ३ ४ ५ ६ ७ ८ ९ क ख ग घ ङ च", 1);
+		let l = LanguageRaw::from_text(&*src).unwrap();
+		let (transition, tt, td) = lang_inner::tables::tabularize(&l);
+		let expected = vec![
+			TokType::Int(1u8.into(), None),
+			TokType::Identifier("en".to_string(), "क१".as_bytes().to_vec()),
+			TokType::Int(1u8.into(), None),
+			TokType::Identifier("en".to_string(), "क".as_bytes().to_vec()),
+		];
+		match lang::tokenize("१ क१ १क".bytes(), &l, &buf, (&transition, &tt, &td)) {
+			Ok(res) => {
+				for i in res.iter() { println!("{:?}", i) }
+				assert_eq!(res.len(), expected.len(), "Returned wrong number of tokens");
+				for (l, r) in res.iter().zip(expected.iter()) {
+					assert_eq!(l, r, "Incorrect token")
+				}
+			}
+			Err(err) => assert!(false, "Failed parsing: {}", err)
+		}
+	}
+}
+
+#[cfg(test)]
+mod floats {
+	use lang::tok::Decimal;
+	use num_bigint::BigUint;
+	use super::*;
+	
+	macro_rules! float_test {
+	    ($name:ident, $test:literal, $(($m:literal, $e:literal)),*$(,)?) => {
+			#[test]
+			fn $name() -> Result<(), lang::LexError> {
+				let expected = vec![$(TokType::Float(Decimal { mantissa: BigUint::from($m as u64), exponent: $e }, None)),*];
+				let buf = Vec::new();
+				let (l, m) = lang::get("en", &buf).unwrap();
+				let toks = lang::tokenize($test.bytes(), l, &buf, m)?;
+				for t in toks.iter() { println!("{:?}", t) }
+				assert_eq!(toks.len(), expected.len(), "Incorrect number of tokens returned");
+				for (tok, tt) in toks.iter().zip(expected.iter()) {
+					assert_eq!(tok, tt)
+				}
+				Ok(())
+			}
+		};
+	}
+	
+	float_test!(decimal, "1.5 0.25 10.50 3. 0.0 007.10", (15, -1), (25, -2), (105, -1), (3, 0), (0, 0), (71, -1));
+	float_test!(exponent, "1.5e-3 2e10 1.5E+2 0e5 12.e1", (15, -4), (2, 10), (15, 1), (0, 0), (12, 1));
+	float_test!(separators, "1_000.2_5 1e1_0 2.5e-0_1", (100025, -2), (1, 10), (25, -2));
+
+	#[test]
+	fn misplaced_separators() {
+		let float = |m: u8, exponent| TokType::Float(Decimal { mantissa: m.into(), exponent }, None);
+		let ident = |t: &str| TokType::Identifier("en".to_string(), t.as_bytes().to_vec());
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		for (test, expected) in [
+			("1._5", vec![float(1, 0), ident("_5")]),
+			("1.5_", vec![float(15, -1), ident("_")]),
+			("1.5__5", vec![float(15, -1), ident("__5")]),
+			("1e5_", vec![float(1, 5), ident("_")]),
+		] {
+			let toks = lang::tokenize(test.bytes(), l, &buf, m).unwrap();
+			assert_eq!(toks.into_iter().map(|t| t.tt).collect::<Vec<_>>(), expected, "{}", test);
+		}
+	}
+	
+	#[test]
+	fn to_f64() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let toks = lang::tokenize("1.5e-3 0.1".bytes(), l, &buf, m).unwrap();
+		let floats = toks.iter().map(|t| match &t.tt {
+			TokType::Float(f, _) => f.to_f64(),
+			t => panic!("Expected float, found {:?}", t)
+		}).collect::<Vec<_>>();
+		assert_eq!(floats, vec![1.5e-3, 0.1])
+	}
+	
+	#[test]
+	fn float_dot() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let toks = lang::tokenize("1.5.a 1e".bytes(), l, &buf, m).unwrap();
+		let expected = vec![
+			TokType::Float(Decimal { mantissa: 15u8.into(), exponent: -1 }, None),
+			TokType::Dot,
+			TokType::Identifier("en".to_string(), "a".as_bytes().to_vec()),
+			TokType::Int(1u8.into(), None),
+			TokType::Identifier("en".to_string(), "e".as_bytes().to_vec()),
+		];
+		assert_eq!(toks.len(), expected.len(), "Incorrect number of tokens returned");
+		for (tok, tt) in toks.iter().zip(expected.iter()) {
+			assert_eq!(tok, tt)
+		}
+	}
+	
+	#[test]
+	fn localised() {
+		let buf = Vec::new();
+		let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+		let src = src.replacen("b x o 0 1 2 3 4 5 6 7 8 9 a b c d e f A B C D E F", "b x o ० १ २ ३ ४ ५ ६ ७ ८ ९ क ख ग घ ङ च", 1);
+		let l = LanguageRaw::from_text(&*src).unwrap();
+		let (transition, tt, td) = lang_inner::tables::tabularize(&l);
+		let toks = lang::tokenize("१.५e-२ ०.२५".bytes(), &l, &buf, (&transition, &tt, &td)).unwrap();
+		let expected = vec![
+			TokType::Float(Decimal { mantissa: 15u8.into(), exponent: -3 }, None),
+			TokType::Float(Decimal { mantissa: 25u8.into(), exponent: -2 }, None),
+		];
+		assert_eq!(toks.len(), expected.len(), "Incorrect number of tokens returned");
+		for (tok, tt) in toks.iter().zip(expected.iter()) {
+			assert_eq!(tok, tt)
+		}
+	}
 }
 
 #[cfg(test)]
@@ -119,7 +362,7 @@ mod comments {
 		let s = r"\\ some comment".to_string() + "\n" + r"123";
 		let expected = vec![
 			TokType::Comment("en".to_string(), " some comment".as_bytes().to_vec()),
-			TokType::Int(123u16.into())
+			TokType::Int(123u16.into(), None)
 		];
 		
 		let buf = Vec::new();
@@ -145,9 +388,9 @@ mod comments {
 			TokType::Comment("en".to_string(), "no spaces".as_bytes().to_vec()),
 			TokType::Comment("en".to_string(), " spaces ".as_bytes().to_vec()),
 			TokType::NewLine(NewLine::Implicit),
-			TokType::Int(123u16.into()),
+			TokType::Int(123u16.into(), None),
 			TokType::Comment("en".to_string(), " inline ".as_bytes().to_vec()),
-			TokType::Int(456u16.into()),
+			TokType::Int(456u16.into(), None),
 			TokType::NewLine(NewLine::Implicit),
 			TokType::Comment("en".to_string(), " over several\nlines ".as_bytes().to_vec()),
 			TokType::Op(Op::Plus)
@@ -165,6 +408,40 @@ mod comments {
 			Err(err) => assert!(false, "Failed parsing: {}", err)
 		}
 	}
+	
+	#[test]
+	fn doc() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let s = "\\\\\\ line doc\n!!de\n\\** block\n doc **\\ \\**\\ \\* a **\\";
+		let res = lang::tokenize(s.bytes(), l, &buf, m).unwrap().into_iter().filter(lang::comments_filter).collect::<Vec<_>>();
+		assert_eq!(res, vec![
+			TokType::DocComment("en".to_string(), b" line doc".to_vec()),
+			TokType::LanguageSwitch("de".to_string()),
+			TokType::DocComment("de".to_string(), b" block\n doc *".to_vec()),
+		]);
+		assert_eq!(res[2].pe.offset, s.find(" \\**\\").unwrap());
+	}
+	
+	#[test]
+	fn nested() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize(r"\* a \* b \* c *\ *\ d \**\ *\ 1 \** e \* f *\ *\".bytes(), l, &buf, m).unwrap();
+		assert_eq!(res, vec![
+			TokType::Comment("en".to_string(), br" a \* b \* c *\ *\ d \**\ ".to_vec()),
+			TokType::Int(1u8.into(), None),
+			TokType::DocComment("en".to_string(), br" e \* f *\ ".to_vec()),
+		]);
+	}
+	
+	#[test]
+	fn block_closing_stars() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize(r"\* a **\ 1".bytes(), l, &buf, m).unwrap();
+		assert_eq!(res, vec![TokType::Comment("en".to_string(), b" a *".to_vec()), TokType::Int(1u8.into(), None)]);
+	}
 }
 
 #[cfg(test)]
@@ -201,10 +478,10 @@ mod idents {
 		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
 		let s = "0 ident i0 0i".to_string();
 		let expected = vec![
-			TokType::Int(0u16.into()),
+			TokType::Int(0u16.into(), None),
 			TokType::Identifier("en".to_string(), "ident".as_bytes().to_vec()),
 			TokType::Identifier("en".to_string(), "i0".as_bytes().to_vec()),
-			TokType::Int(0u16.into()),
+			TokType::Int(0u16.into(), None),
 			TokType::Identifier("en".to_string(), "i".as_bytes().to_vec()),
 		];
 		
@@ -324,6 +601,76 @@ mod idents {
 			TokType::Identifier("en".to_string(), "ident".as_bytes().to_vec()),
 		];
 		
+		let buf = Vec::new();
+		match lang::tokenize(s.bytes(), l, &buf, m) {
+			Ok(res) => {
+				for i in res.iter() { println!("{:?}", i) }
+				assert_eq!(res.len(), expected.len(), "Returned wrong number of tokens");
+				for (l, r) in res.iter().zip(expected.iter()) {
+					assert_eq!(l, r, "Incorrect token")
+				}
+			}
+			Err(err) => assert!(false, "Failed parsing: {}", err)
+		}
+	}	
+	#[test]
+	fn unicode() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let s = "größe 名前 _x1 x_".to_string();
+		let expected = vec![
+			TokType::Identifier("en".to_string(), "größe".as_bytes().to_vec()),
+			TokType::Identifier("en".to_string(), "名前".as_bytes().to_vec()),
+			TokType::Identifier("en".to_string(), "_x1".as_bytes().to_vec()),
+			TokType::Identifier("en".to_string(), "x_".as_bytes().to_vec()),
+		];
+		
+		let buf = Vec::new();
+		match lang::tokenize(s.bytes(), l, &buf, m) {
+			Ok(res) => {
+				for i in res.iter() { println!("{:?}", i) }
+				assert_eq!(res.len(), expected.len(), "Returned wrong number of tokens");
+				for (l, r) in res.iter().zip(expected.iter()) {
+					assert_eq!(l, r, "Incorrect token")
+				}
+			}
+			Err(err) => assert!(false, "Failed parsing: {}", err)
+		}
+	}
+	
+	#[test]
+	fn normalized() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let s = "e\u{301} \u{e9}".to_string();
+		let expected = vec![
+			TokType::Identifier("en".to_string(), "é".as_bytes().to_vec()),
+			TokType::Identifier("en".to_string(), "é".as_bytes().to_vec()),
+		];
+		
+		let buf = Vec::new();
+		match lang::tokenize(s.bytes(), l, &buf, m) {
+			Ok(res) => {
+				for i in res.iter() { println!("{:?}", i) }
+				assert_eq!(res.len(), expected.len(), "Returned wrong number of tokens");
+				for (l, r) in res.iter().zip(expected.iter()) {
+					assert_eq!(l, r, "Incorrect token")
+				}
+			}
+			Err(err) => assert!(false, "Failed parsing: {}", err)
+		}
+	}
+	
+	#[test]
+	fn kwd_unicode_suffix() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let s = "elseé elsé".to_string();
+		let expected = vec![
+			TokType::Identifier("en".to_string(), "elseé".as_bytes().to_vec()),
+			TokType::Identifier("en".to_string(), "elsé".as_bytes().to_vec()),
+		];
+		
 		let buf = Vec::new();
 		match lang::tokenize(s.bytes(), l, &buf, m) {
 			Ok(res) => {
@@ -410,6 +757,63 @@ mod string_char {
 				Err(err) => assert!(false, "Failed parsing: {}", err)
 			}
 		}
+		
+		#[test]
+		fn escapes() {
+			let buf = Vec::new();
+			let (l, m) = lang::get("en", &buf).unwrap();
+			for (input, expected) in [
+				(r#""a\"b""#, "a\"b"),
+				(r#""\\\0\t\r""#, "\\\0\t\r"),
+				(r#""\'\u{41}\u{1F600}""#, "'A\u{1F600}"),
+			] {
+				let res = lang::tokenize(input.bytes(), l, &buf, m).unwrap();
+				assert_eq!(res, vec![TokType::String(expected.as_bytes().to_vec())], "Incorrect string for {}", input);
+			}
+		}
+		
+		#[test]
+		fn raw() {
+			let buf = Vec::new();
+			let (l, m) = lang::get("en", &buf).unwrap();
+			for (input, expected) in [
+				(r#"r"C:\dir\n""#, r"C:\dir\n"),
+				(r#"r"""#, ""),
+				(r##"r#"say "hi""#"##, r#"say "hi""#),
+				(r###"r##"a"#b"##"###, r##"a"#b"##),
+			] {
+				let res = lang::tokenize(input.bytes(), l, &buf, m).unwrap();
+				assert_eq!(res, vec![TokType::String(expected.as_bytes().to_vec())], "Incorrect string for {}", input);
+			}
+		}
+		
+		#[test]
+		fn raw_after_token() {
+			let buf = Vec::new();
+			let (l, m) = lang::get("de", &buf).unwrap();
+			let res = lang::tokenize(r#"a=r"\d" r b"#.bytes(), l, &buf, m).unwrap();
+			assert_eq!(res[2], Token {
+				ps: Position { ln: 0, col: 2, offset: 2, char_col: 2, utf16_col: 2 },
+				pe: Position { ln: 0, col: 7, offset: 7, char_col: 7, utf16_col: 7 },
+				tt: TokType::String(br"\d".to_vec()) });
+			assert_eq!(res.len(), 5);
+		}
+		
+		#[test]
+		fn block() {
+			let buf = Vec::new();
+			let (l, m) = lang::get("en", &buf).unwrap();
+			let input = "a = \"\"\"  \n    first\n      \"second\"\t\\u{41}\r\n\n  short\n    \"\"\"\nb";
+			let res = lang::tokenize(input.bytes(), l, &buf, m).unwrap();
+			assert_eq!(res[2], Token {
+				ps: Position { ln: 0, col: 4, offset: 4, char_col: 4, utf16_col: 4 },
+				pe: Position { ln: 5, col: 7, offset: 59, char_col: 7, utf16_col: 7 },
+				tt: TokType::String(b"first\n  \"second\"\tA\n\nshort".to_vec())
+			});
+			let input = "r\"\"\"\n\t\\n \"\"\n\t\"\"\"";
+			let res = lang::tokenize(input.bytes(), l, &buf, m).unwrap();
+			assert_eq!(res, vec![TokType::String(b"\\n \"\"".to_vec())]);
+		}
 	}
 	
 	mod char {
@@ -437,6 +841,16 @@ mod string_char {
 			}
 		}
 		
+		#[test]
+		fn escapes() {
+			let buf = Vec::new();
+			let (l, m) = lang::get("en", &buf).unwrap();
+			for (input, expected) in [(r"'\''", '\''), (r"'\\'", '\\'), (r"'\0'", '\0'), (r"'\u{e9}'", 'é')] {
+				let res = lang::tokenize(input.bytes(), l, &buf, m).unwrap();
+				assert_eq!(res, vec![TokType::Char(expected)], "Incorrect char for {}", input);
+			}
+		}
+		
 		#[test]
 		fn double_char_fail() {
 			let buf = Vec::new();
@@ -450,3 +864,172 @@ mod string_char {
 		}
 	}
 }
+
+#[cfg(test)]
+mod interpolation {
+	use super::*;
+	
+	fn tokenize(s: &str) -> Vec<TokType> {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		lang::tokenize(s.bytes(), l, &buf, m).unwrap().into_iter().map(|t| t.tt).collect()
+	}
+	
+	fn ident(lang: &str, i: &str) -> TokType {
+		TokType::Identifier(lang.to_string(), i.as_bytes().to_vec())
+	}
+	
+	#[test]
+	fn simple() {
+		assert_eq!(tokenize("\"hello {name}!\""), vec![
+			TokType::StringStart(b"hello ".to_vec()),
+			TokType::InterpStart, ident("en", "name"), TokType::InterpEnd,
+			TokType::StringPart(b"!".to_vec()),
+		]);
+	}
+	
+	#[test]
+	fn multiple() {
+		assert_eq!(tokenize("\"{a}{ {b} }\" c"), vec![
+			TokType::StringStart(Vec::new()),
+			TokType::InterpStart, ident("en", "a"), TokType::InterpEnd,
+			TokType::StringPart(Vec::new()),
+			TokType::InterpStart, TokType::LParenCurly, ident("en", "b"), TokType::RParenCurly, TokType::InterpEnd,
+			TokType::StringPart(Vec::new()),
+			ident("en", "c"),
+		]);
+	}
+	
+	#[test]
+	fn nested() {
+		assert_eq!(tokenize("\"a {\"b {c}\"} d\""), vec![
+			TokType::StringStart(b"a ".to_vec()),
+			TokType::InterpStart,
+			TokType::StringStart(b"b ".to_vec()),
+			TokType::InterpStart, ident("en", "c"), TokType::InterpEnd,
+			TokType::StringPart(Vec::new()),
+			TokType::InterpEnd,
+			TokType::StringPart(b" d".to_vec()),
+		]);
+	}
+	
+	#[test]
+	fn language_switch() {
+		let res = tokenize("\"{!!de\na} b\" c");
+		assert_eq!(res[3], ident("de", "a"));
+		assert_eq!(res[6], ident("en", "c"));
+	}
+	
+	#[test]
+	fn escaped_braces() {
+		assert_eq!(tokenize(r#""\{a\}""#), vec![TokType::String(b"{a}".to_vec())]);
+		assert_eq!(tokenize(r#"r"{a}""#), vec![TokType::String(b"{a}".to_vec())]);
+	}
+	
+	#[test]
+	fn positions() {
+		let buf = Vec::new();
+		let (l, m) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize("\"a{b}c\"".bytes(), l, &buf, m).unwrap();
+		let pos = |col| Position { ln: 0, col, offset: col, char_col: col, utf16_col: col };
+		let spans = res.iter().map(|t| (t.ps, t.pe)).collect::<Vec<_>>();
+		assert_eq!(spans, vec![(pos(0), pos(2)), (pos(2), pos(3)), (pos(3), pos(4)), (pos(4), pos(5)), (pos(5), pos(7))]);
+	}
+}
+
+#[cfg(test)]
+mod language_switch {
+	use lang::Lexer;
+	use super::*;
+	
+	#[test]
+	fn switch_token() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize("a !!Deutsch\nb;!!en;".bytes(), l, &buf, m).unwrap();
+		assert_eq!(res[1], Token {
+			ps: Position { ln: 0, col: 2, offset: 2, char_col: 2, utf16_col: 2 },
+			pe: Position { ln: 1, col: 0, offset: 12, char_col: 0, utf16_col: 0 },
+			tt: TokType::LanguageSwitch("de".to_string()),
+		});
+		assert_eq!(res[2].tt, TokType::Identifier("de".to_string(), b"b".to_vec()));
+		assert_eq!(res[4], Token {
+			ps: Position { ln: 1, col: 2, offset: 14, char_col: 2, utf16_col: 2 },
+			pe: Position { ln: 1, col: 7, offset: 19, char_col: 7, utf16_col: 7 },
+			tt: TokType::LanguageSwitch("en".to_string()),
+		});
+	}
+	
+	#[test]
+	fn suppressed() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let s = "a\n!!de\nb";
+		let res = Lexer::new(s.bytes(), l, &buf, m).language_switches(false).collect::<Result<Vec<_>, _>>().unwrap();
+		let filtered = lang::tokenize(s.bytes(), l, &buf, m).unwrap().into_iter().filter(lang::language_switch_filter).collect::<Vec<_>>();
+		assert_eq!(res.len(), 3);
+		assert_eq!(res, filtered);
+	}
+}
+
+#[cfg(test)]
+mod positions {
+	use lang::tok::NewLine;
+	use super::*;
+	
+	fn pos(ln: usize, col: usize, offset: usize, char_col: usize, utf16_col: usize) -> Position {
+		Position { ln, col, offset, char_col, utf16_col }
+	}
+	
+	#[test]
+	fn multi_byte() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let s = "äb = '😀' c";
+		let res = lang::tokenize(s.bytes(), l, &buf, m).unwrap();
+		let spans = res.iter().map(|t| (t.ps, t.pe)).collect::<Vec<_>>();
+		assert_eq!(spans, vec![
+			(pos(0, 0, 0, 0, 0), pos(0, 3, 3, 2, 2)),
+			(pos(0, 4, 4, 3, 3), pos(0, 5, 5, 4, 4)),
+			(pos(0, 6, 6, 5, 5), pos(0, 12, 12, 8, 9)),
+			(pos(0, 13, 13, 9, 10), pos(0, 14, 14, 10, 11)),
+		]);
+		assert_eq!(&s[res[2].ps.offset..res[2].pe.offset], "'😀'");
+	}
+	
+	#[test]
+	fn crlf() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize("a\r\nb\r\n".bytes(), l, &buf, m).unwrap();
+		assert_eq!(res, vec![
+			Token { ps: pos(0, 0, 0, 0, 0), pe: pos(0, 1, 1, 1, 1), tt: TokType::Identifier("en".to_string(), b"a".to_vec()) },
+			Token { ps: pos(0, 2, 2, 2, 2), pe: pos(1, 0, 3, 0, 0), tt: TokType::NewLine(NewLine::Implicit) },
+			Token { ps: pos(1, 0, 3, 0, 0), pe: pos(1, 1, 4, 1, 1), tt: TokType::Identifier("en".to_string(), b"b".to_vec()) },
+			Token { ps: pos(1, 2, 5, 2, 2), pe: pos(2, 0, 6, 0, 0), tt: TokType::NewLine(NewLine::Implicit) },
+		]);
+	}
+	
+	#[test]
+	fn crlf_language_switch() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize("!!de\r\nsetz".bytes(), l, &buf, m).unwrap();
+		assert_eq!(res, vec![
+			Token { ps: pos(0, 0, 0, 0, 0), pe: pos(1, 0, 6, 0, 0), tt: TokType::LanguageSwitch("de".to_string()) },
+			Token { ps: pos(1, 0, 6, 0, 0), pe: pos(1, 4, 10, 4, 4), tt: TokType::ControlKeyword(lang::tok::ControlKeyword::KSet) },
+		]);
+	}
+	
+	#[test]
+	fn crlf_comments() {
+		let buf = Vec::new();
+		let (l, m): (&LanguageRaw, (&dyn Table<u16>, &dyn Table<u8>, &dyn Table<u8>)) = lang::get("en", &buf).unwrap();
+		let res = lang::tokenize("\\\\ a\r\n\\\\\\ b\r\nc".bytes(), l, &buf, m).unwrap();
+		assert_eq!(res, vec![
+			Token { ps: pos(0, 0, 0, 0, 0), pe: pos(1, 0, 6, 0, 0), tt: TokType::Comment("en".to_string(), b" a".to_vec()) },
+			Token { ps: pos(1, 0, 6, 0, 0), pe: pos(2, 0, 13, 0, 0), tt: TokType::DocComment("en".to_string(), b" b".to_vec()) },
+			Token { ps: pos(2, 0, 13, 0, 0), pe: pos(2, 1, 14, 1, 1), tt: TokType::Identifier("en".to_string(), b"c".to_vec()) },
+		]);
+	}
+}
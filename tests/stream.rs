@@ -0,0 +1,76 @@
+use lang::{Lexer, LexError};
+use lang::tok::{Position, Token, TokType};
+use std::io::{Cursor, Read};
+
+#[test]
+fn matches_tokenize() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let input = "int a = 5 \\\\ comment\n{ !!de\n b }\n'c' \"str\"";
+	let toks = lang::tokenize(input.bytes(), l, &buf, m).unwrap();
+	let from_slice = Lexer::from_slice(input.as_bytes(), l, &buf, m).collect::<Result<Vec<_>, _>>();
+	assert_eq!(from_slice, Ok(toks.clone()));
+	let from_reader = Lexer::from_reader(Cursor::new(input), l, &buf, m).collect::<Result<Vec<_>, _>>();
+	assert_eq!(from_reader, Ok(toks));
+}
+
+#[test]
+fn lazy() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	// reading past the second identifier panics
+	let bytes = "a b".bytes().chain(std::iter::from_fn(|| panic!("read too far")));
+	let mut lexer = Lexer::new(bytes, l, &buf, m);
+	assert_eq!(lexer.next().unwrap().unwrap().tt, TokType::Identifier("en".to_string(), b"a".to_vec()));
+}
+
+#[test]
+fn stops_at_error() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let mut lexer = Lexer::from_slice(b"a } b", l, &buf, m);
+	assert!(lexer.next().unwrap().is_ok());
+	assert!(matches!(lexer.next(), Some(Err(LexError::UnmatchedBrace { .. }))));
+	assert!(lexer.next().is_none());
+}
+
+#[test]
+fn recover() {
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let input = "a } b";
+	let (toks, errors) = lang::tokenize_recover(input.bytes(), l, &buf, m);
+	let (ok, err): (Vec<_>, Vec<_>) = Lexer::from_slice(input.as_bytes(), l, &buf, m).recover(true).partition(Result::is_ok);
+	assert_eq!(ok.into_iter().map(Result::unwrap).collect::<Vec<_>>(), toks);
+	assert_eq!(err.into_iter().map(Result::unwrap_err).collect::<Vec<_>>(), errors);
+}
+
+#[test]
+fn io_error() {
+	struct Failing(usize);
+
+	impl Read for Failing {
+		fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+			if self.0 == 0 { return Err(std::io::Error::other("failed")) }
+			self.0 = 0;
+			out[..2].copy_from_slice(b"a ");
+			Ok(2)
+		}
+	}
+
+	let buf = Vec::new();
+	let (l, m) = lang::get("en", &buf).unwrap();
+	let mut lexer = Lexer::from_reader(Failing(1), l, &buf, m);
+	assert_eq!(lexer.by_ref().collect::<Vec<_>>(), vec![
+		Ok(Token { ps: Position::default(), pe: Position { col: 1, offset: 1, char_col: 1, utf16_col: 1, ..Default::default() }, tt: TokType::Identifier("en".to_string(), b"a".to_vec()) }),
+		Err(LexError::ReadError {
+			pos: Position { col: 2, offset: 2, char_col: 2, utf16_col: 2, ..Default::default() },
+			error: "failed".to_string(),
+			lang: "en".to_string(),
+		}),
+	]);
+	assert_eq!(lexer.source().error().unwrap().to_string(), "failed");
+	// a failed read is not a complete input
+	let res = Lexer::from_reader(Failing(1), l, &buf, m).collect::<Result<Vec<_>, _>>();
+	assert_eq!(res.map_err(|e| e.code()), Err((0, 9)));
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use lang::LanguageTuple;
+
+fn translate(s: &str, from: &str, to: &str, buf: &[LanguageTuple<'static>]) -> String {
+	let (from, m) = lang::get(from, buf).unwrap();
+	let (to, _) = lang::get(to, buf).unwrap();
+	let toks = lang::tokenize(s.bytes(), from, buf, m).unwrap();
+	lang::translate(s.as_bytes(), &toks, from, buf, to)
+}
+
+#[test]
+fn keywords() {
+	assert_eq!(translate("set a = not b and c", "en", "de", &[]), "setz a = nicht b und c");
+	assert_eq!(translate("setz a = nicht b und c", "de", "en", &[]), "set a = not b and c");
+}
+
+#[test]
+fn verbatim() {
+	let s = "\\\\ set\nset a = \"set {set}\" \\* set *\\ set";
+	assert_eq!(translate(s, "en", "de", &[]), "\\\\ set\nsetz a = \"set {setz}\" \\* set *\\ setz");
+}
+
+#[test]
+fn numbers() {
+	let buf = vec![common::right_to_left()];
+	let s = "0x1F_ff 1_000int 2.5e3 0b10 0o7 true";
+	let expected = "٠x١f_ff ١_٠٠٠ent ٢.٥e٣ ٠b١٠ ٠o٧ vrai";
+	assert_eq!(translate(s, "en", "xx", &buf), expected);
+	assert_eq!(translate(expected, "xx", "en", &buf), "0x1f_ff 1_000int 2.5e3 0b10 0o7 true");
+}
+
+#[test]
+fn brackets() {
+	let buf = vec![common::right_to_left()];
+	assert_eq!(translate("f(a[0]) {}", "en", "xx", &buf), "f)a]٠[( {}");
+	assert_eq!(translate("f)a]٠[( {}", "xx", "en", &buf), "f(a[0]) {}");
+	assert_eq!(translate("f(a[0])", "en", "de", &buf), "f(a[0])");
+}
+
+#[test]
+fn language_switch() {
+	assert_eq!(translate("set a\n!!de\nsetz b\n!!en;set c", "en", "de", &[]), "setz a\n\nsetz b\n;setz c");
+}
+
+#[test]
+fn scoped_language_switch() {
+	let buf = vec![common::right_to_left()];
+	// the switch only lasts until the end of the braces or interpolation
+	assert_eq!(translate("{!!xx\n١ent} 2int", "en", "de", &buf), "{\n1int} 2int");
+	assert_eq!(translate("\"{!!xx\n١ent}\" 2int", "en", "de", &buf), "\"{\n1int}\" 2int");
+}
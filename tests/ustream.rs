@@ -48,11 +48,11 @@ fn ser_de() -> Result<(), String> {
         let transition_ustream = UStream::compress(&transition);
         let tt_ustream = UStream::compress(&tt);
         let td_ustream = UStream::compress(&td);
-        let deser = deserialize_bin(((&transition_ustream, &tt_ustream, &td_ustream)));
-        let mut deser = deser.iter().cloned();
+        let ser = serialize_bin((&transition_ustream, &tt_ustream, &td_ustream));
+        let mut ser = ser.iter().cloned();
         let (ser_de_transition_ustream, ser_de_tt_ustream, ser_de_td_ustream) =
-            serialize_bin(&mut deser).ok_or("Deserialization error".to_string())?;
-        assert!(deser.next().is_none());
+            deserialize_bin(&mut ser).ok_or("Deserialization error".to_string())?;
+        assert!(ser.next().is_none());
         for i in 0..transition.len() as u16 {
             for n in 0..=255 {
                 assert_eq!(
@@ -73,3 +73,45 @@ fn ser_de() -> Result<(), String> {
         }
         Ok(())
     }
+
+mod pack {
+    use lang_inner::compress::{Compress, UStream, pack::{LanguagePack, PackError, MAGIC}};
+    use lang_inner::{LanguageRaw, Table};
+
+    fn en_pack() -> LanguagePack {
+        let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+        let lang = LanguageRaw::from_text(&src).unwrap();
+        let (transition, tt, td) = lang_inner::tables::tabularize(&lang);
+        LanguagePack::new(&lang, (UStream::compress(&transition), UStream::compress(&tt), UStream::compress(&td)))
+    }
+
+    #[test]
+    fn round_trip() {
+        let pack = en_pack();
+        let bytes = pack.to_bytes();
+        assert_eq!(bytes[..4], MAGIC);
+        let de = LanguagePack::from_bytes(&bytes).unwrap();
+        assert_eq!(de.text, pack.text);
+        assert_eq!(de.language().unwrap().name, ("English", "en"));
+        for i in 0..pack.tables.0.offsets.len() as u16 {
+            for n in 0..=255 {
+                assert_eq!(de.tables.0.element(i, n), pack.tables.0.element(i, n));
+                assert_eq!(de.tables.1.element(i, n), pack.tables.1.element(i, n));
+                assert_eq!(de.tables.2.element(i, n), pack.tables.2.element(i, n));
+            }
+        }
+    }
+
+    #[test]
+    fn corrupt() {
+        let bytes = en_pack().to_bytes();
+        assert_eq!(LanguagePack::from_bytes(&bytes[1..]).err(), Some(PackError::Magic));
+        assert_eq!(LanguagePack::from_bytes(&bytes[..8]).err(), Some(PackError::Length));
+        let mut version = bytes.clone();
+        version[5] = 0xff;
+        assert_eq!(LanguagePack::from_bytes(&version).err(), Some(PackError::Version(0xff)));
+        let mut flipped = bytes.clone();
+        flipped[20] ^= 1;
+        assert!(matches!(LanguagePack::from_bytes(&flipped), Err(PackError::Checksum { .. })));
+    }
+}
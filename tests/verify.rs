@@ -0,0 +1,71 @@
+use lang_inner::LanguageRaw;
+use lang_inner::verify::{Finding, FindingKind, Section};
+
+/// Verify English with the given (0 indexed) lines replaced
+fn verify_with(replace: &[(usize, &str)]) -> Vec<Finding> {
+	let src = std::fs::read_to_string("languages/en.fckl").unwrap();
+	let src = src.lines().enumerate()
+		.map(|(i, l)| replace.iter().find(|(n, _)| *n == i).map_or(l, |(_, r)| *r))
+		.collect::<Vec<_>>()
+		.join("\n");
+	let l = LanguageRaw::from_text(&src).unwrap();
+	l.verify()
+}
+
+#[test]
+fn valid() {
+	assert_eq!(verify_with(&[]), vec![])
+}
+
+#[test]
+fn keyword_collision() {
+	let findings = verify_with(&[(3, "struct properties enum variants self Self for extend const")]);
+	assert_eq!(findings, vec![Finding {
+		line: 4,
+		kind: FindingKind::KeywordCollision { keyword: "for".to_string(), first: Section::Control }
+	}]);
+	assert_eq!(findings[0].to_string(), "line 4: keyword `for` collides with `for` in control keywords")
+}
+
+#[test]
+fn symbol_collision() {
+	assert_eq!(verify_with(&[(5, "true ==")]), vec![Finding {
+		line: 6,
+		kind: FindingKind::KeywordCollision { keyword: "==".to_string(), first: Section::Symbols }
+	}])
+}
+
+#[test]
+fn digits() {
+	assert_eq!(verify_with(&[(1, "b 1 o 0 1 2 3 4 5 6 7 8 9 a b c d e f A B C D E F")]), vec![Finding {
+		line: 2, kind: FindingKind::PrefixReused('1')
+	}]);
+	assert_eq!(verify_with(&[(1, "b x o 0 1 2 3 4 5 6 7 8 9 a b c d e f A B C D E ९")]), vec![Finding {
+		line: 2, kind: FindingKind::DigitLengthsDiffer
+	}]);
+	assert_eq!(verify_with(&[(1, "b x o 0 1 2 3 4 5 6 7 8 9 a b c d e a A B C D E F")]), vec![Finding {
+		line: 2, kind: FindingKind::DuplicateDigit('a')
+	}]);
+	assert_eq!(verify_with(&[(1, "b x e 0 1 2 3 4 5 6 7 8 9 a b c d e f A B C D E F")]), vec![Finding {
+		line: 2, kind: FindingKind::ExponentMarker('e')
+	}]);
+}
+
+#[test]
+fn cli() {
+	assert_eq!(verify_with(&[(57, "path h")]), vec![Finding {
+		line: 58, kind: FindingKind::DuplicateShortFlag('h')
+	}]);
+	assert_eq!(verify_with(&[(41, "new")]), vec![Finding {
+		line: 42, kind: FindingKind::DuplicateCommand("new".to_string())
+	}]);
+}
+
+#[test]
+fn multiple() {
+	let findings = verify_with(&[(0, "{ English e!"), (6, "package package src tests benches type lib app version authors github gitlab email license description readme homepage repo features dependencies usage git branch path dev build")]);
+	assert_eq!(findings, vec![
+		Finding { line: 1, kind: FindingKind::InvalidCode("e!".to_string()) },
+		Finding { line: 7, kind: FindingKind::DuplicateManifestKey("package".to_string()) },
+	])
+}